@@ -8,11 +8,11 @@ use crate::{
             Area, Curve,
         },
     },
-    gds::ElementsGroup,
+    gds::{ElementsGroup, PathType},
     Quantity,
 };
 
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub struct LayerData {
     pub(crate) layer: i16,
     pub(crate) datatype: i16,
@@ -90,6 +90,17 @@ where
     }
 }
 
+impl<Q, C> Decorated<LayerData> for (Curve<C>, Q, PathType<Q>)
+where
+    Q: Quantity,
+    C: IntoIterator<Item = Coordinate<Q>> + 'static,
+{
+    type Quantity = Q;
+    fn color(self, c: LayerData) -> ElementsGroup<Self::Quantity> {
+        ElementsGroup::Single(self.0.width_path_with(self.1, self.2, c))
+    }
+}
+
 impl<Q, T1, T2> Decorated<LayerData> for Compound<T1, T2>
 where
     Q: Quantity,
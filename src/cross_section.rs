@@ -0,0 +1,143 @@
+use crate::{
+    color::LayerData,
+    draw::curve::groups::Group,
+    units::{Absolute, Length, LengthType},
+    zero, Num,
+};
+
+//what is drawn across a path, the bands are swept one by one with their edges at `edges` from the centerline
+pub trait Profile<Q> {
+    //`(right, left)` edges of every band, positive to the left of the path
+    fn edges(&self) -> Vec<(Q, Q)>;
+    //layers of the bands, `None` draws them with the colour of the cursor
+    fn layers(&self) -> Option<Group<LayerData>> {
+        None
+    }
+}
+
+//plain widths are bands centered on the path
+impl<L: LengthType, T: Num, W: AsRef<[Length<L, T>]>> Profile<Length<L, T>> for W {
+    fn edges(&self) -> Vec<(Length<L, T>, Length<L, T>)> {
+        let two = T::one() + T::one();
+        self.as_ref()
+            .iter()
+            .map(|w| (-*w / two, *w / two))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Band<L: LengthType = Absolute, T: Num = f64> {
+    pub(crate) layer: LayerData,
+    pub(crate) width: Length<L, T>,
+    //of the band center, positive to the left of the path
+    pub(crate) offset: Length<L, T>,
+    //`(length, width)` of the tip drawn beyond the ends of a path, see `Assembler::cap_start`
+    pub(crate) end_taper: Option<(Length<L, T>, Length<L, T>)>,
+}
+
+impl<L: LengthType, T: Num> Band<L, T> {
+    pub fn new(layer: LayerData, width: Length<L, T>, offset: Length<L, T>) -> Self {
+        Self {
+            layer,
+            width,
+            offset,
+            end_taper: None,
+        }
+    }
+    pub fn with_end_taper(mut self, length: Length<L, T>, width: Length<L, T>) -> Self {
+        self.end_taper = Some((length, width));
+        self
+    }
+    pub fn layer(&self) -> LayerData {
+        self.layer
+    }
+    pub fn width(&self) -> Length<L, T> {
+        self.width
+    }
+    pub fn offset(&self) -> Length<L, T> {
+        self.offset
+    }
+    pub fn end_taper(&self) -> Option<(Length<L, T>, Length<L, T>)> {
+        self.end_taper
+    }
+    //right and left edges of a band of `width` around the center of this one
+    pub(crate) fn edges_with(&self, width: Length<L, T>) -> (Length<L, T>, Length<L, T>) {
+        let half = width / (T::one() + T::one());
+        (self.offset - half, self.offset + half)
+    }
+}
+
+//bands of any layer, width and offset, e.g. a rib with its slab, a strip with an off-center heater or the two rails
+//of a slot waveguide
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrossSection<L: LengthType = Absolute, T: Num = f64> {
+    pub(crate) bands: Vec<Band<L, T>>,
+}
+
+impl<L: LengthType, T: Num> CrossSection<L, T> {
+    pub fn new(bands: Vec<Band<L, T>>) -> Self {
+        Self { bands }
+    }
+    //the same as plain widths drawn with `layers`
+    pub fn centered(layers: &[LayerData], widths: &[Length<L, T>]) -> Self {
+        Self {
+            bands: layers
+                .iter()
+                .zip(widths)
+                .map(|(layer, width)| Band::new(*layer, *width, zero()))
+                .collect(),
+        }
+    }
+    pub fn bands(&self) -> &[Band<L, T>] {
+        &self.bands
+    }
+}
+
+impl<L: LengthType, T: Num> From<Vec<Band<L, T>>> for CrossSection<L, T> {
+    fn from(bands: Vec<Band<L, T>>) -> Self {
+        Self::new(bands)
+    }
+}
+
+impl<L: LengthType, T: Num> Profile<Length<L, T>> for CrossSection<L, T> {
+    fn edges(&self) -> Vec<(Length<L, T>, Length<L, T>)> {
+        self.bands.iter().map(|b| b.edges_with(b.width)).collect()
+    }
+    fn layers(&self) -> Option<Group<LayerData>> {
+        Some(Group(self.bands.iter().map(|b| b.layer).collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MICROMETER;
+
+    #[test]
+    fn band_edges() {
+        let widths = [MICROMETER * 2., MICROMETER * 6.];
+        assert_eq!(
+            widths.edges(),
+            vec![
+                (-MICROMETER, MICROMETER),
+                (-MICROMETER * 3., MICROMETER * 3.)
+            ]
+        );
+        assert_eq!(widths.layers(), None);
+
+        let layers = [LayerData::new(1, 0), LayerData::new(2, 0)];
+        let centered = CrossSection::centered(&layers, &widths);
+        assert_eq!(centered.edges(), widths.edges());
+        assert_eq!(centered.layers(), Some(Group::from(layers)));
+
+        //slot waveguide, two rails with a gap of 0.2 between them
+        let slot = CrossSection::new(vec![
+            Band::new(layers[0], MICROMETER * 0.4, MICROMETER * 0.3),
+            Band::new(layers[0], MICROMETER * 0.4, -MICROMETER * 0.3),
+        ]);
+        let edges = slot.edges();
+        assert!((edges[0].0.value - 0.1).abs() < 1e-12);
+        assert!((edges[1].1.value + 0.1).abs() < 1e-12);
+    }
+}
@@ -1,20 +1,27 @@
-use std::mem;
+use std::{collections::HashMap, marker::PhantomData, mem};
 
-use nalgebra::{RealField, Rotation, Translation};
+use log::warn;
+use nalgebra::RealField;
 use num::{traits::FloatConst, Float, FromPrimitive, Zero};
 
 use crate::{
     color::{Colour, LayerData},
+    cross_section::{CrossSection, Profile},
     draw::{
-        coordinate::{Coordinate, LenCo, MulAsScalar},
+        bbox::{Bounded, BoundingBox},
+        coordinate::{Coordinate, LenCo},
         curve::{
             groups::{Compound, Group},
-            Area, Curve, Sweep,
+            Area, Curve, LeftBias, Sweep, SweepBy,
         },
-        transfer::{CommonTrans, MulOpClosure, Transfer},
+        euler::EulerBend,
+        parametric::ParametricCurve,
+        sbend::{SBend, SBendShape},
+        spiral::{ArchimedeanSpiral, DoubleSpiral},
+        transfer::{CommonTrans, Transform, Transformed},
         CircularArc, Line, Resolution,
     },
-    gds::DgirCell,
+    gds::{DgirCell, Port, Ref},
     units::{Absolute, Angle, Length, LengthType},
     zero, Num, Quantity,
 };
@@ -27,6 +34,8 @@ where
 {
     pub pos: LenCo<L, T>,
     pub dir: Angle<T>,
+    //centerline length assembled so far, see `Cursor::advance`
+    pub length: PathLength<L, T>,
 }
 
 impl<L: LengthType, T: Num> Default for Cursor<L, T> {
@@ -34,6 +43,7 @@ impl<L: LengthType, T: Num> Default for Cursor<L, T> {
         Self {
             pos: LenCo::from([Zero::zero(), Zero::zero()]),
             dir: Angle::from_rad(Zero::zero()),
+            length: PathLength::default(),
         }
     }
 }
@@ -43,8 +53,69 @@ impl<L: LengthType, T: Num> Cursor<L, T> {
         Self {
             pos: pos.into(),
             dir,
+            length: PathLength::default(),
         }
     }
+    //takes an element starting at `start_pos` along `start_ang` to the cursor
+    pub fn placement(&self, start_pos: LenCo<L, T>, start_ang: Angle<T>) -> Transform<T>
+    where
+        T: RealField + Float,
+    {
+        self.placement_as(start_pos, start_ang, false)
+    }
+    //`mirrored` reflects the element about the direction of travel, a left turn is drawn to the right
+    pub(crate) fn placement_as(
+        &self,
+        start_pos: LenCo<L, T>,
+        start_ang: Angle<T>,
+        mirrored: bool,
+    ) -> Transform<T>
+    where
+        T: RealField + Float,
+    {
+        let t = Transform::translation(-start_pos[0], -start_pos[1]).rotate(-start_ang);
+        let t = if mirrored { t.mirror_x() } else { t };
+        t.rotate(self.dir).translate(self.pos[0], self.pos[1])
+    }
+    //moves to the end of an element placed with `placement_as`, the element is only known by its ends, so its length
+    //is taken along the circular arc through them: exact for lines and arcs, the chord for an s-bend and a little
+    //short for an euler bend, `Assembler::path_length` counts those exactly
+    pub(crate) fn advance(
+        &mut self,
+        (start_pos, end_pos): (LenCo<L, T>, LenCo<L, T>),
+        (start_ang, end_ang): (Angle<T>, Angle<T>),
+        mirrored: bool,
+    ) where
+        T: RealField + Float,
+    {
+        let (dx, dy) = (
+            (end_pos[0] - start_pos[0]).value,
+            (end_pos[1] - start_pos[1]).value,
+        );
+        let chord = Float::hypot(dx, dy);
+        let half = (end_ang - start_ang).to_rad() / (T::one() + T::one());
+        let lateral = dy * Float::cos(start_ang.to_rad()) - dx * Float::sin(start_ang.to_rad());
+        let tol = T::from_f64(1e-9).unwrap();
+        let len = |v: T| Length {
+            value: v,
+            marker: PhantomData,
+        };
+        if Float::abs(half) <= tol && Float::abs(lateral) <= chord * tol {
+            self.length.straight += len(chord);
+        } else if Float::abs(Float::sin(half)) <= tol {
+            self.length.bend += len(chord);
+        } else {
+            self.length.bend += len(chord * Float::abs(half / Float::sin(half)));
+        }
+        self.pos = self
+            .placement_as(start_pos, start_ang, mirrored)
+            .apply(end_pos);
+        self.dir = if mirrored {
+            self.dir - (end_ang - start_ang)
+        } else {
+            self.dir + end_ang - start_ang
+        };
+    }
     pub fn with_cell<Cell: AsMut<DgirCell<Length<L, T>>>, C: Colour>(
         self,
         cell: Cell,
@@ -54,10 +125,27 @@ impl<L: LengthType, T: Num> Cursor<L, T> {
             cell,
             color,
             cursor: self,
+            mirrored: false,
+            states: Vec::new(),
+            marks: HashMap::new(),
         }
     }
-    //WHAT THE FUCK
-    pub fn assemble<E>(&mut self, e: E) -> <<<E as Transfer<Length<L, T>>>::Output<MulOpClosure<MulAsScalar<Translation<T, 2_usize>>>> as Transfer<Length<L, T>>>::Output<MulOpClosure<MulAsScalar<Rotation<T, 2_usize>>>> as Transfer<Length<L, T>>>::Output<MulOpClosure<MulAsScalar<Translation<T, 2_usize>>>>
+    pub fn assemble<E>(&mut self, e: E) -> Transformed<E, L, T>
+    where
+        T: RealField + Float,
+        E: CommonTrans<L, T> + Pos<Length<L, T>> + Dir<T>,
+    {
+        self.assemble_as(e, false)
+    }
+    //the other hand of `assemble`, the element is reflected about the direction of travel
+    pub fn assemble_mirrored<E>(&mut self, e: E) -> Transformed<E, L, T>
+    where
+        T: RealField + Float,
+        E: CommonTrans<L, T> + Pos<Length<L, T>> + Dir<T>,
+    {
+        self.assemble_as(e, true)
+    }
+    pub(crate) fn assemble_as<E>(&mut self, e: E, mirrored: bool) -> Transformed<E, L, T>
     where
         T: RealField + Float,
         E: CommonTrans<L, T> + Pos<Length<L, T>> + Dir<T>,
@@ -66,12 +154,8 @@ impl<L: LengthType, T: Num> Cursor<L, T> {
         let end_pos = e.end_pos();
         let start_ang = e.start_ang();
         let end_ang = e.end_ang();
-        let e = e
-            .translate(-start_pos[0], -start_pos[1])
-            .rotate(self.dir - start_ang)
-            .translate(self.pos[0], self.pos[1]);
-        self.pos = self.pos + (end_pos - start_pos).rotate(self.dir - start_ang);
-        self.dir = self.dir + end_ang - start_ang;
+        let e = e.transform(self.placement_as(start_pos, start_ang, mirrored));
+        self.advance((start_pos, end_pos), (start_ang, end_ang), mirrored);
         e
     }
 }
@@ -86,14 +170,35 @@ pub struct CellCursor<
     pub cursor: Cursor<L, T>,
     cell: Cell,
     pub color: C,
+    //elements are reflected about the direction of travel, a left turn is drawn to the right
+    mirrored: bool,
+    states: Vec<Saved<C, L, T>>,
+    marks: HashMap<String, Saved<C, L, T>>,
 }
 
+//what a cell cursor goes back to, the cursor with its mirror mode and colour
+type Saved<C, L, T> = (Cursor<L, T>, bool, C);
+
 impl<C: Colour, L: LengthType, T: Num> CellCursor<DgirCell<Length<L, T>>, C, L, T> {
     pub fn new<S: ToString>(cell_name: S, color: C) -> Self {
         Self {
             cursor: Cursor::default(),
             cell: DgirCell::new(cell_name),
             color,
+            mirrored: false,
+            states: Vec::new(),
+            marks: HashMap::new(),
+        }
+    }
+}
+
+//a cursor at the port continues out of the cell
+impl<L: LengthType, T: Num + FromPrimitive> From<Port<Length<L, T>>> for Cursor<L, T> {
+    fn from(port: Port<Length<L, T>>) -> Self {
+        Self {
+            pos: port.pos(),
+            dir: port.dir(),
+            length: PathLength::default(),
         }
     }
 }
@@ -106,12 +211,15 @@ impl<Cell: AsMut<DgirCell<Length<L, T>>>, C: Colour, L: LengthType, T: Num>
             cursor: Cursor::default(),
             cell,
             color,
+            mirrored: false,
+            states: Vec::new(),
+            marks: HashMap::new(),
         }
     }
-    pub fn with_assembler<W: AsRef<[Length<L, T>]>>(
+    pub fn with_assembler<W: Profile<Length<L, T>>>(
         self,
         width: W,
-        res: Resolution,
+        res: Resolution<Length<L, T>>,
     ) -> Assembler<Cell, W, L, T>
     where
         C: Into<Group<LayerData>>,
@@ -121,9 +229,17 @@ impl<Cell: AsMut<DgirCell<Length<L, T>>>, C: Colour, L: LengthType, T: Num>
                 cell: self.cell,
                 color: self.color.into(),
                 cursor: self.cursor,
+                mirrored: self.mirrored,
+                states: Vec::new(),
+                marks: HashMap::new(),
             },
             res,
             width,
+            radius: None,
+            strands: None,
+            length: PathLength::default(),
+            states: Vec::new(),
+            marks: HashMap::new(),
         }
     }
     pub fn mut_cell(&mut self) -> &mut DgirCell<Length<L, T>> {
@@ -132,130 +248,808 @@ impl<Cell: AsMut<DgirCell<Length<L, T>>>, C: Colour, L: LengthType, T: Num>
     pub fn into_cell(self) -> Cell {
         self.cell
     }
+    pub fn mirrored(&self) -> bool {
+        self.mirrored
+    }
+    pub fn set_mirrored(&mut self, mirrored: bool) -> &mut Self {
+        self.mirrored = mirrored;
+        self
+    }
+    //`Cursor::assemble` in the mirror mode of the cell cursor
+    pub(crate) fn assemble<E>(&mut self, e: E) -> Transformed<E, L, T>
+    where
+        T: RealField + Float,
+        E: CommonTrans<L, T> + Pos<Length<L, T>> + Dir<T>,
+    {
+        self.cursor.assemble_as(e, self.mirrored)
+    }
+    pub(crate) fn placement(&self, start_pos: LenCo<L, T>, start_ang: Angle<T>) -> Transform<T>
+    where
+        T: RealField + Float,
+    {
+        self.cursor
+            .placement_as(start_pos, start_ang, self.mirrored)
+    }
+    pub(crate) fn advance(&mut self, ends: (LenCo<L, T>, LenCo<L, T>), angles: (Angle<T>, Angle<T>))
+    where
+        T: RealField + Float,
+    {
+        self.cursor.advance(ends, angles, self.mirrored);
+    }
     pub fn assemble_in<E>(&mut self, e: E) -> &mut Self
     where
         T: RealField + Float,
         E: CommonTrans<L, T> + Pos<Length<L, T>> + Dir<T>,
-        <<<E as Transfer<Length<L, T>>>::Output<MulOpClosure<MulAsScalar<Translation<T, 2_usize>>>> as Transfer<Length<L, T>>>::Output<MulOpClosure<MulAsScalar<Rotation<T, 2_usize>>>> as Transfer<Length<L, T>>>::Output<MulOpClosure<MulAsScalar<Translation<T, 2_usize>>>>:crate::color::Decorated<C,Quantity=Length<L,T>>,
+        Transformed<E, L, T>: crate::color::Decorated<C, Quantity = Length<L, T>>,
     {
-        self.cell
-            .as_mut()
-            .push(self.color.clone().color(self.cursor.assemble(e)));
+        let e = self.assemble(e);
+        self.cell.as_mut().push(self.color.clone().color(e));
+        self
+    }
+    //saves the cursor, the mirror mode and the colour, `pop_state` goes back to the last one saved
+    pub fn push_state(&mut self) -> &mut Self {
+        self.states.push(self.saved());
+        self
+    }
+    pub fn pop_state(&mut self) -> &mut Self {
+        match self.states.pop() {
+            Some(saved) => self.restore(saved),
+            None => warn!("no saved cursor state to pop"),
+        }
+        self
+    }
+    //a bookmark can be gone back to any number of times, one with the same name is replaced
+    pub fn bookmark<S: ToString>(&mut self, name: S) -> &mut Self {
+        self.marks.insert(name.to_string(), self.saved());
         self
     }
+    pub fn goto(&mut self, name: &str) -> &mut Self {
+        match self.marks.get(name) {
+            Some(saved) => self.restore(saved.clone()),
+            None => warn!("bookmark {} not found", name),
+        }
+        self
+    }
+    fn saved(&self) -> Saved<C, L, T> {
+        (self.cursor, self.mirrored, self.color.clone())
+    }
+    fn restore(&mut self, (cursor, mirrored, color): Saved<C, L, T>) {
+        self.cursor = cursor;
+        self.mirrored = mirrored;
+        self.color = color;
+    }
+    //places `cell` with its `input` port at the cursor, the cursor continues from its `output` port, `None` if
+    //either port is missing and nothing is placed
+    pub fn place(
+        &mut self,
+        cell: DgirCell<Length<L, T>>,
+        input: &str,
+        output: &str,
+    ) -> Option<&mut Self>
+    where
+        T: Float + FloatConst + FromPrimitive,
+    {
+        self.place_ref(cell.into_ref(), input, output)
+    }
+    pub fn place_ref(
+        &mut self,
+        r: Ref<Length<L, T>>,
+        input: &str,
+        output: &str,
+    ) -> Option<&mut Self>
+    where
+        T: Float + FloatConst + FromPrimitive,
+    {
+        let mut r = r;
+        //a mirrored cursor places the other hand of the cell
+        if self.mirrored {
+            r.set_mirror(!r.mirrored());
+        }
+        r.connect(input, &self.cursor)?;
+        //the route goes on through the cell, what is inside it is not counted
+        self.cursor = Cursor {
+            length: self.cursor.length,
+            ..r.port(output)?.into()
+        };
+        self.cell.as_mut().push(r);
+        Some(self)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaperShape {
+    Linear,
+    //`w1 * (w2 / w1)^t`, linear if either width is not positive
+    Exponential,
+    //the square of the width grows linearly, the slowest opening at the narrow end
+    Parabolic,
+}
+
+impl TaperShape {
+    //width at the fraction `t` of the length from `w1` to `w2`
+    pub fn width_at<T: Float>(&self, w1: T, w2: T, t: T) -> T {
+        match self {
+            TaperShape::Exponential if w1 > T::zero() && w2 > T::zero() => w1 * (w2 / w1).powf(t),
+            TaperShape::Parabolic => (w1 * w1 + (w2 * w2 - w1 * w1) * t).max(T::zero()).sqrt(),
+            _ => w1 + (w2 - w1) * t,
+        }
+    }
+}
+
+//centerline length drawn by an `Assembler` or assembled by a `Cursor`, s-bends and swept curves are counted as bends
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathLength<L: LengthType = Absolute, T: Num = f64> {
+    pub straight: Length<L, T>,
+    pub bend: Length<L, T>,
+    pub taper: Length<L, T>,
+}
+
+impl<L: LengthType, T: Num> Default for PathLength<L, T> {
+    fn default() -> Self {
+        Self {
+            straight: zero(),
+            bend: zero(),
+            taper: zero(),
+        }
+    }
+}
+
+impl<L: LengthType, T: Num> PathLength<L, T> {
+    pub fn total(&self) -> Length<L, T> {
+        self.straight + self.bend + self.taper
+    }
 }
 
 #[derive(Debug)]
 pub struct Assembler<
     Cell: AsMut<DgirCell<Length<L, T>>>,
-    W: AsRef<[Length<L, T>]>,
+    W: Profile<Length<L, T>>,
     L: LengthType = Absolute,
     T: Num = f64,
 > {
     pub cell_cur: CellCursor<Cell, Group<LayerData>, L, T>,
     pub width: W,
-    pub res: Resolution,
+    pub res: Resolution<Length<L, T>>,
+    //radius of the bends made by the routers
+    pub radius: Option<Length<L, T>>,
+    //right and left edges of every layer in merge mode, which are joined into one polygon when flushed
+    strands: Option<Vec<Strand<L, T>>>,
+    length: PathLength<L, T>,
+    states: Vec<State<W, L, T>>,
+    marks: HashMap<String, State<W, L, T>>,
 }
 
-impl<W: AsRef<[Length<L, T>]>, L: LengthType, T: Num> Assembler<DgirCell<Length<L, T>>, W, L, T> {
+//what a branch goes back to, the cursor with the mirror mode, colour and width it draws with
+type State<W, L, T> = (Saved<Group<LayerData>, L, T>, W);
+
+type Strand<L, T> = (Vec<LenCo<L, T>>, Vec<LenCo<L, T>>);
+
+//edges already taken from a profile
+#[derive(Debug, Clone)]
+struct Edges<L: LengthType, T: Num>(Vec<(Length<L, T>, Length<L, T>)>);
+
+impl<L: LengthType, T: Num> Profile<Length<L, T>> for Edges<L, T> {
+    fn edges(&self) -> Vec<(Length<L, T>, Length<L, T>)> {
+        self.0.clone()
+    }
+}
+
+fn distance<L: LengthType, T: Num + Float>(a: LenCo<L, T>, b: LenCo<L, T>) -> T {
+    Float::hypot((b[0] - a[0]).value, (b[1] - a[1]).value)
+}
+
+//length of a curve along its drawn points
+fn centerline_length<L: LengthType, T: Num + Float, C: IntoIterator<Item = LenCo<L, T>>>(
+    curve: C,
+) -> Length<L, T> {
+    let center: Vec<_> = curve.into_iter().collect();
+    Length {
+        value: center
+            .windows(2)
+            .fold(T::zero(), |s, w| s + distance(w[0], w[1])),
+        marker: PhantomData,
+    }
+}
+
+//points closer than this are one junction: a thousandth of the resolution, or a few ulps of the
+//coordinates when the resolution has no length
+fn junction_tolerance<L: LengthType, T: Num + Float + FromPrimitive>(
+    res: Resolution<Length<L, T>>,
+    a: LenCo<L, T>,
+    b: LenCo<L, T>,
+) -> T {
+    match res {
+        Resolution::MinDistance(d) | Resolution::MaxDeviation(d) => {
+            Float::abs(d.value) / T::from_u32(1000).unwrap()
+        }
+        Resolution::MinNumber(_) => {
+            let scale = [a[0], a[1], b[0], b[1]]
+                .into_iter()
+                .fold(T::zero(), |m, c| Float::max(m, Float::abs(c.value)));
+            scale * T::epsilon() * T::from_u32(1024).unwrap()
+        }
+    }
+}
+
+fn strand_area<L: LengthType, T: Num>((right, left): Strand<L, T>) -> Area<Vec<LenCo<L, T>>> {
+    let mut area = right;
+    area.extend(left.into_iter().rev());
+    area.push(area[0]);
+    Area { area }
+}
+
+impl<W: Profile<Length<L, T>>, L: LengthType, T: Num> Assembler<DgirCell<Length<L, T>>, W, L, T> {
     pub fn new<S: ToString, C: Into<Group<LayerData>>>(
         cell_name: S,
         color: C,
         width: W,
-        res: Resolution,
+        res: Resolution<Length<L, T>>,
     ) -> Self {
         Self {
             cell_cur: CellCursor {
                 cursor: Cursor::default(),
                 cell: DgirCell::new(cell_name),
                 color: color.into(),
+                mirrored: false,
+                states: Vec::new(),
+                marks: HashMap::new(),
             },
             width,
             res,
+            radius: None,
+            strands: None,
+            length: PathLength::default(),
+            states: Vec::new(),
+            marks: HashMap::new(),
         }
     }
 }
-impl<Cell: AsMut<DgirCell<Length<L, T>>>, W: AsRef<[Length<L, T>]>, L: LengthType, T: Num>
+impl<Cell: AsMut<DgirCell<Length<L, T>>>, W: Profile<Length<L, T>>, L: LengthType, T: Num>
     Assembler<Cell, W, L, T>
 {
-    pub fn new_from_cell(cell: Cell, color: Group<LayerData>, width: W, res: Resolution) -> Self {
+    pub fn new_from_cell(
+        cell: Cell,
+        color: Group<LayerData>,
+        width: W,
+        res: Resolution<Length<L, T>>,
+    ) -> Self {
         Self {
             cell_cur: CellCursor {
                 cursor: Cursor::default(),
                 cell,
                 color,
+                mirrored: false,
+                states: Vec::new(),
+                marks: HashMap::new(),
             },
             width,
             res,
+            radius: None,
+            strands: None,
+            length: PathLength::default(),
+            states: Vec::new(),
+            marks: HashMap::new(),
         }
     }
+    //edges not flushed yet are not in the cell
     pub fn mut_cell(&mut self) -> &mut DgirCell<Length<L, T>> {
         self.cell_cur.mut_cell()
     }
-    pub fn into_cell(self) -> Cell {
+    pub fn into_cell(mut self) -> Cell {
+        self.flush();
         self.cell_cur.cell
     }
+    //jumping breaks the outline, so the merged polygons are flushed first
     pub fn set_pos<P: Into<Coordinate<Length<L, T>>>>(&mut self, p: P) -> &mut Self {
+        self.flush();
         self.cell_cur.cursor.pos = p.into();
         self
     }
     pub fn set_dir(&mut self, a: Angle<T>) -> &mut Self {
+        self.flush();
         self.cell_cur.cursor.dir = a;
         self
     }
+    //the following segments are drawn reflected about the direction of travel, see `CellCursor::mirrored`
+    pub fn set_mirrored(&mut self, mirrored: bool) -> &mut Self {
+        self.flush();
+        self.cell_cur.set_mirrored(mirrored);
+        self
+    }
+    //saves the cursor, the colour and the width, `pop_state` goes back to the last one saved
+    pub fn push_state(&mut self) -> &mut Self
+    where
+        W: Clone,
+    {
+        self.states
+            .push((self.cell_cur.saved(), self.width.clone()));
+        self
+    }
+    //going back breaks the outline like a jump
+    pub fn pop_state(&mut self) -> &mut Self {
+        self.flush();
+        match self.states.pop() {
+            Some(state) => self.restore(state),
+            None => warn!("no saved cursor state to pop"),
+        }
+        self
+    }
+    //a bookmark can be gone back to any number of times, one with the same name is replaced
+    pub fn bookmark<S: ToString>(&mut self, name: S) -> &mut Self
+    where
+        W: Clone,
+    {
+        self.marks.insert(
+            name.to_string(),
+            (self.cell_cur.saved(), self.width.clone()),
+        );
+        self
+    }
+    pub fn goto(&mut self, name: &str) -> &mut Self
+    where
+        W: Clone,
+    {
+        self.flush();
+        match self.marks.get(name) {
+            Some(state) => self.restore(state.clone()),
+            None => warn!("bookmark {} not found", name),
+        }
+        self
+    }
+    fn restore(&mut self, (saved, width): State<W, L, T>) {
+        self.cell_cur.restore(saved);
+        self.width = width;
+    }
+    //length drawn since the assembler is made or the length is reset
+    pub fn path_length(&self) -> PathLength<L, T> {
+        self.length
+    }
+    pub fn reset_length(&mut self) -> &mut Self {
+        self.length = PathLength::default();
+        self
+    }
+    pub fn set_radius(&mut self, radius: Length<L, T>) -> &mut Self {
+        self.radius = Some(radius);
+        self
+    }
+    //in merge mode the consecutive segments of every layer are joined into one polygon instead of one per segment
+    pub fn set_merge(&mut self, merge: bool) -> &mut Self {
+        self.flush();
+        self.strands = merge.then(Vec::new);
+        self
+    }
+    //a cross-section brings its own layers, plain widths take the colour
+    fn layers(&self) -> Group<LayerData> {
+        self.width
+            .layers()
+            .unwrap_or_else(|| self.cell_cur.color.clone())
+    }
+    //pushes the merged polygons into the cell, the next segment starts new ones
+    pub fn flush(&mut self) -> &mut Self {
+        let layers = self.layers();
+        if let Some(strands) = self.strands.as_mut() {
+            for (strand, layer) in strands.drain(..).zip(layers.0.iter()) {
+                if strand.0.len() + strand.1.len() > 2 {
+                    self.cell_cur
+                        .cell
+                        .as_mut()
+                        .push((*layer).color(strand_area(strand)));
+                }
+            }
+        }
+        self
+    }
+    pub fn place(
+        &mut self,
+        cell: DgirCell<Length<L, T>>,
+        input: &str,
+        output: &str,
+    ) -> Option<&mut Self>
+    where
+        T: Float + FloatConst + FromPrimitive,
+    {
+        self.flush();
+        self.cell_cur.place(cell, input, output)?;
+        Some(self)
+    }
+    pub fn place_ref(
+        &mut self,
+        r: Ref<Length<L, T>>,
+        input: &str,
+        output: &str,
+    ) -> Option<&mut Self>
+    where
+        T: Float + FloatConst + FromPrimitive,
+    {
+        self.flush();
+        self.cell_cur.place_ref(r, input, output)?;
+        Some(self)
+    }
+    //port at the cursor facing the cursor direction, the first band is taken as the port width, a profile without
+    //bands has no port
+    pub fn add_port(&mut self, name: impl ToString) -> &mut Self {
+        let cursor = &self.cell_cur.cursor;
+        let Some(&(right, left)) = self.width.edges().first() else {
+            warn!(
+                "port {} not added, the profile has no bands",
+                name.to_string()
+            );
+            return self;
+        };
+        let port = Port::new(name, cursor.pos, cursor.dir, left - right, self.layers());
+        self.cell_cur.cell.as_mut().add_port(port);
+        self
+    }
 }
-impl<
-        Cell: AsMut<DgirCell<Length<Absolute, f64>>>,
-        W: 'static + Clone + AsRef<[Length<Absolute, f64>]>,
-    > Assembler<Cell, W, Absolute, f64>
+impl<Cell, W, L, T> Assembler<Cell, W, L, T>
+where
+    Cell: AsMut<DgirCell<Length<L, T>>>,
+    W: 'static + Clone + Profile<Length<L, T>>,
+    L: LengthType,
+    T: Num + Float + FloatConst + FromPrimitive + RealField,
 {
-    pub fn turn(&mut self, radius: Length<Absolute, f64>, a: Angle<f64>) -> &mut Self {
-        self.cell_cur.assemble_in(
-            ArcCurve::new(
-                CircularArc::new_origin(radius, (Angle::from_deg(0.), a), self.res),
-                self.width.clone(),
-            )
-            .into_group(),
-        );
+    pub fn turn(&mut self, radius: Length<L, T>, a: Angle<T>) -> &mut Self {
+        self.length.bend += radius.abs() * Float::abs(a.to_rad());
+        let arc = CircularArc::new_origin(radius, (Angle::from_rad(T::zero()), a), self.res);
+        self.push_swept(|cursor, edges| cursor.assemble(ArcCurve::new(arc, edges).into_group()).0);
         self
     }
-    pub fn extend(&mut self, len: Length<Absolute, f64>) -> &mut Self {
-        assert!(len.is_positive());
+    //`p` is the fraction of the angle taken by the euler sections, see `EulerBend`
+    pub fn euler_turn(&mut self, min_radius: Length<L, T>, a: Angle<T>, p: T) -> &mut Self {
+        let bend = EulerBend::new(min_radius, a, p, self.res);
+        self.length.bend += bend.length();
+        self.push_swept(|cursor, edges| {
+            cursor.assemble(EulerCurve::new(bend, edges).into_group()).0
+        });
+        self
+    }
+    //raised cosine s-bend, the direction is kept
+    pub fn sbend(&mut self, len: Length<L, T>, offset: Length<L, T>) -> &mut Self {
+        self.sbend_with(SBendShape::Cosine, len, offset)
+    }
+    pub fn sbend_with(
+        &mut self,
+        shape: SBendShape,
+        len: Length<L, T>,
+        offset: Length<L, T>,
+    ) -> &mut Self {
+        let dir = self.cell_cur.cursor.dir;
+        let bend = SBend::new(shape, len, offset, self.res);
+        self.length.bend += bend.length();
+        self.push_swept(|cursor, edges| {
+            cursor.assemble(SBendCurve::new(bend, edges).into_group()).0
+        });
+        self.cell_cur.cursor.dir = dir;
+        self
+    }
+    //sweeps any curve with all the widths, e.g. a `ParametricCurve`
+    pub fn sweep_in<S>(&mut self, curve: S) -> &mut Self
+    where
+        S: Sweep<Length<L, T>>
+            + IntoIterator<Item = LenCo<L, T>>
+            + Pos<Length<L, T>>
+            + Dir<T>
+            + Clone,
+        <S::Output as IntoIterator>::IntoIter: 'static,
+    {
+        self.length.bend += centerline_length(curve.clone());
+        self.push_swept(|cursor, edges| {
+            let g = Group(
+                edges
+                    .0
+                    .into_iter()
+                    .map(|e| LocatIter::locat_sweep(curve.clone(), e))
+                    .collect::<Vec<_>>(),
+            );
+            cursor.assemble(g).0
+        });
+        self
+    }
+    //sweeps any curve while every band changes linearly from the current profile to `width`, e.g. a tapered bend
+    pub fn sweep_to<S>(&mut self, curve: S, width: W) -> &mut Self
+    where
+        S: IntoIterator<Item = LenCo<L, T>> + Pos<Length<L, T>> + Dir<T> + Clone,
+    {
+        self.length.bend += centerline_length(curve.clone());
+        let (start_pos, end_pos) = (curve.start_pos(), curve.end_pos());
+        let (start_ang, end_ang) = (curve.start_ang(), curve.end_ang());
+        let placement = self.cell_cur.placement(start_pos, start_ang);
+        let areas = self
+            .width
+            .edges()
+            .into_iter()
+            .zip(width.edges())
+            .map(|((r1, l1), (r2, l2))| {
+                curve
+                    .clone()
+                    .sweep_by(|t| (r1 + (r2 - r1) * t, l1 + (l2 - l1) * t))
+                    .transform(placement)
+            })
+            .collect::<Vec<_>>();
+        self.push_areas(areas);
         self.cell_cur
-            .assemble_in(Rect::from_length(len, self.width.clone()).into_group());
+            .advance((start_pos, end_pos), (start_ang, end_ang));
+        if width.layers() != self.width.layers() {
+            self.flush();
+        }
+        self.width = width;
         self
     }
-    pub fn taper(&mut self, len: Length<Absolute, f64>, width: W) -> &mut Self {
-        use crate::color::Decorated;
-        let g = Group::from(
-            self.width
-                .as_ref()
-                .iter()
-                .zip(width.as_ref().iter())
-                .map(|(w1, w2)| {
-                    Area {
-                        area: [
-                            Coordinate::from([zero(), *w1 / 2.]),
-                            Coordinate::from([zero(), -*w1 / 2.]),
-                            Coordinate::from([len, -*w2 / 2.]),
-                            Coordinate::from([len, *w2 / 2.]),
-                            Coordinate::from([zero(), *w1 / 2.]),
-                        ],
-                    }
-                    .rotate(self.cell_cur.cursor.dir)
-                    .translate(self.cell_cur.cursor.pos[0], self.cell_cur.cursor.pos[1])
-                })
-                .collect::<Vec<_>>(),
+    pub fn extend(&mut self, len: Length<L, T>) -> &mut Self {
+        assert!(len.is_positive());
+        self.length.straight += len;
+        self.push_swept(|cursor, edges| {
+            cursor
+                .assemble(Rect::from_length(len, edges).into_group())
+                .0
+        });
+        self
+    }
+    pub fn taper(&mut self, len: Length<L, T>, width: W) -> &mut Self {
+        self.taper_with(TaperShape::Linear, len, width)
+    }
+    pub fn taper_with(&mut self, shape: TaperShape, len: Length<L, T>, width: W) -> &mut Self {
+        let straight = shape == TaperShape::Linear;
+        self.taper_along(len, width, straight, |w1, w2, t| shape.width_at(w1, w2, t))
+    }
+    //`profile` takes the fraction of the length and gives the fraction of the width change, both from 0 to 1
+    pub fn taper_by<F: Fn(T) -> T>(
+        &mut self,
+        len: Length<L, T>,
+        width: W,
+        profile: F,
+    ) -> &mut Self {
+        self.taper_along(len, width, false, |w1, w2, t| w1 + (w2 - w1) * profile(t))
+    }
+    //the center of every band moves linearly, its width follows `width_at(w1, w2, t)`
+    fn taper_along<F: Fn(T, T, T) -> T>(
+        &mut self,
+        len: Length<L, T>,
+        width: W,
+        straight: bool,
+        width_at: F,
+    ) -> &mut Self {
+        self.length.taper += len;
+        let two = T::one() + T::one();
+        let bands: Vec<_> = self
+            .width
+            .edges()
+            .into_iter()
+            .zip(width.edges())
+            .map(|((r1, l1), (r2, l2))| {
+                let (c1, c2) = ((r1 + l1).value / two, (r2 + l2).value / two);
+                (c1, c2, (l1 - r1).value, (l2 - r2).value)
+            })
+            .collect();
+        let edges = |(c1, c2, w1, w2): (T, T, T, T), t: T| {
+            let (c, w) = (c1 + (c2 - c1) * t, width_at(w1, w2, t));
+            (c - w / two, c + w / two)
+        };
+        let sections = if straight {
+            1
+        } else {
+            self.taper_sections(len.value, |b, t| edges(bands[b], t), bands.len())
+        };
+        let frac = |k: usize| T::from_usize(k).unwrap() / T::from_usize(sections).unwrap();
+        let x = |k: usize| len * frac(k);
+        let to_len = |v: T| Length {
+            value: v,
+            marker: PhantomData,
+        };
+        let (ends, along) = (
+            ([zero(), zero()].into(), [len, zero()].into()),
+            Angle::from_rad(T::zero()),
         );
-        self.cell_cur
-            .cell
-            .as_mut()
-            .push(g.color(self.cell_cur.color.clone()));
-        self.cell_cur.cursor.pos = self.cell_cur.cursor.pos
-            + Coordinate::from([len, zero()]).rotate(self.cell_cur.cursor.dir);
+        let placement = self.cell_cur.placement(ends.0, along);
+        //the same point order as a sweep, the right edge forward then the left edge backward
+        let areas = bands
+            .iter()
+            .map(|band| {
+                let points: Vec<_> = (0..=sections)
+                    .map(|k| (x(k), edges(*band, frac(k))))
+                    .collect();
+                let right = points
+                    .iter()
+                    .map(|(x, (r, _))| Coordinate::from([*x, to_len(*r)]));
+                let left = points
+                    .iter()
+                    .rev()
+                    .map(|(x, (_, l))| Coordinate::from([*x, to_len(*l)]));
+                let mut area: Vec<_> = right.chain(left).collect();
+                area.push(area[0]);
+                Area { area }.transform(placement)
+            })
+            .collect::<Vec<_>>();
+        self.push_areas(areas);
+        self.cell_cur.advance(ends, (along, along));
+        //bands moving to other layers start new polygons
+        if width.layers() != self.width.layers() {
+            self.flush();
+        }
         self.width = width;
         self
     }
+    //sections of a taper of `len` whose `n` bands have edges `edges(band, t)`
+    fn taper_sections<F: Fn(usize, T) -> (T, T)>(&self, len: T, edges: F, n: usize) -> usize {
+        let sections = match self.res {
+            Resolution::MinNumber(n) => {
+                debug_assert!(n > 1);
+                n - 1
+            }
+            Resolution::MinDistance(d) => {
+                Float::ceil(Float::abs(len / d.value)).to_usize().unwrap()
+            }
+            //a chord of `h` is off the edge by up to `h^2 * |y''| / 8`
+            Resolution::MaxDeviation(e) => {
+                let samples = 256;
+                let num = |v: usize| T::from_usize(v).unwrap();
+                let h = len / num(samples);
+                let mut curvature = T::zero();
+                for b in 0..n {
+                    let y: Vec<_> = (0..=samples)
+                        .map(|i| edges(b, num(i) / num(samples)))
+                        .collect();
+                    for w in y.windows(3) {
+                        let r = Float::abs(w[2].0 - num(2) * w[1].0 + w[0].0);
+                        let l = Float::abs(w[2].1 - num(2) * w[1].1 + w[0].1);
+                        curvature = Float::max(curvature, Float::max(r, l) / (h * h));
+                    }
+                }
+                Float::ceil(len * Float::sqrt(curvature / (num(8) * e.value)))
+                    .to_usize()
+                    .unwrap()
+            }
+        };
+        sections.max(1)
+    }
+    //`sweep` draws at the cursor with the given edges, in merge mode every edge is swept on its own, as the two
+    //edges of a band may be sampled with different numbers of points
+    fn push_swept<A, F>(&mut self, sweep: F)
+    where
+        A: IntoIterator<Item = LenCo<L, T>>,
+        F: FnOnce(&mut CellCursor<Cell, Group<LayerData>, L, T>, Edges<L, T>) -> Vec<A>,
+    {
+        let edges = self.width.edges();
+        if self.strands.is_none() {
+            let areas = sweep(&mut self.cell_cur, Edges(edges));
+            self.push_areas(areas);
+            return;
+        }
+        let single = edges
+            .iter()
+            .flat_map(|(r, l)| [(*r, *r), (*l, *l)])
+            .collect();
+        //a sweep of no width goes along the edge and back
+        let mut swept = sweep(&mut self.cell_cur, Edges(single))
+            .into_iter()
+            .map(|a| {
+                let mut points: Vec<_> = a.into_iter().collect();
+                points.truncate(points.len() / 2);
+                points
+            });
+        let edges = edges
+            .iter()
+            .map_while(|_| Some((swept.next()?, swept.next()?)))
+            .collect();
+        self.push_edges(edges);
+    }
+    //every area is an outline in sweep order, in merge mode it is split into two edges of the same length
+    fn push_areas<A: IntoIterator<Item = LenCo<L, T>>>(&mut self, areas: Vec<A>) {
+        if self.strands.is_none() {
+            let layers = self.layers();
+            for (a, layer) in areas.into_iter().zip(layers.0.iter()) {
+                let area: Vec<_> = a.into_iter().collect();
+                self.cell_cur
+                    .cell
+                    .as_mut()
+                    .push((*layer).color(Area { area }));
+            }
+            return;
+        }
+        let edges = areas
+            .into_iter()
+            .map(|a| {
+                let mut right: Vec<_> = a.into_iter().collect();
+                if right.len() > 1 && right.first() == right.last() {
+                    right.pop();
+                }
+                let mut left = right.split_off(right.len() / 2);
+                left.reverse();
+                (right, left)
+            })
+            .collect();
+        self.push_edges(edges);
+    }
+    //`(right, left)` edges of every layer in sweep order, appended to the strands
+    fn push_edges(&mut self, edges: Vec<Strand<L, T>>) {
+        if self
+            .strands
+            .as_ref()
+            .is_some_and(|s| !s.is_empty() && s.len() != edges.len())
+        {
+            self.flush();
+        }
+        let layers = self.layers();
+        let res = self.res;
+        let Some(strands) = self.strands.as_mut() else {
+            return;
+        };
+        strands.resize_with(edges.len(), Default::default);
+        for (((right, left), strand), layer) in edges
+            .into_iter()
+            .zip(strands.iter_mut())
+            .zip(layers.0.iter())
+        {
+            //a new polygon starts at the junction before the points limit is hit
+            if !strand.0.is_empty()
+                && strand.0.len() + strand.1.len() + right.len() + left.len()
+                    >= crate::MAX_POINTS_NUM
+            {
+                let junction = (
+                    vec![*strand.0.last().unwrap()],
+                    vec![*strand.1.last().unwrap()],
+                );
+                self.cell_cur
+                    .cell
+                    .as_mut()
+                    .push((*layer).color(strand_area(mem::replace(strand, junction))));
+            }
+            for (edge, points) in [(&mut strand.0, right), (&mut strand.1, left)] {
+                let mut points = points.into_iter().peekable();
+                if let (Some(a), Some(b)) = (edge.last(), points.peek()) {
+                    if distance(*a, *b) <= junction_tolerance(res, *a, *b) {
+                        points.next();
+                    }
+                }
+                edge.extend(points);
+            }
+        }
+    }
+}
+
+impl<Cell, L, T> Assembler<Cell, CrossSection<L, T>, L, T>
+where
+    Cell: AsMut<DgirCell<Length<L, T>>>,
+    L: LengthType,
+    T: Num + Float + FloatConst + FromPrimitive + RealField,
+{
+    //tips of the bands with an end taper drawn ahead of the cursor, which stays where it is
+    pub fn cap_end(&mut self) -> &mut Self {
+        self.caps(T::one())
+    }
+    //the same behind the cursor, for the start of a path
+    pub fn cap_start(&mut self) -> &mut Self {
+        self.caps(-T::one())
+    }
+    fn caps(&mut self, sign: T) -> &mut Self {
+        self.flush();
+        let placement = self
+            .cell_cur
+            .placement([zero(), zero()].into(), Angle::from_rad(T::zero()));
+        for band in self.width.bands.iter() {
+            let Some((len, tip)) = band.end_taper else {
+                continue;
+            };
+            let ((r1, l1), (r2, l2)) = (band.edges_with(band.width), band.edges_with(tip));
+            let x = len * sign;
+            let area = Area {
+                area: [
+                    Coordinate::from([zero(), r1]),
+                    Coordinate::from([x, r2]),
+                    Coordinate::from([x, l2]),
+                    Coordinate::from([zero(), l1]),
+                    Coordinate::from([zero(), r1]),
+                ],
+            }
+            .transform(placement);
+            let area: Vec<_> = area.into_iter().collect();
+            self.cell_cur
+                .cell
+                .as_mut()
+                .push(band.layer.color(Area { area }));
+        }
+        self
+    }
 }
 
 pub trait Pos<Q: Quantity> {
@@ -340,6 +1134,116 @@ impl<L: LengthType, T: Num + FromPrimitive> Dir<T> for CircularArc<L, T> {
     }
 }
 
+impl<L: LengthType, T: Num + Float + FromPrimitive + FloatConst> Pos<Length<L, T>>
+    for EulerBend<L, T>
+{
+    fn start_pos(&self) -> Coordinate<Length<L, T>> {
+        self.point_at(self.range.0)
+    }
+    fn end_pos(&self) -> Coordinate<Length<L, T>> {
+        self.point_at(self.range.1)
+    }
+}
+
+impl<L: LengthType, T: Num + Float + FromPrimitive + FloatConst> Dir<T> for EulerBend<L, T> {
+    fn start_ang(&self) -> Angle<T> {
+        self.ang_at(self.range.0)
+    }
+    fn end_ang(&self) -> Angle<T> {
+        self.ang_at(self.range.1)
+    }
+}
+
+impl<L: LengthType, T: Num + Float + FromPrimitive + FloatConst> Pos<Length<L, T>> for SBend<L, T> {
+    fn start_pos(&self) -> Coordinate<Length<L, T>> {
+        self.point_at(T::zero())
+    }
+    fn end_pos(&self) -> Coordinate<Length<L, T>> {
+        self.point_at(T::one())
+    }
+}
+
+impl<L: LengthType, T: Num + Float + FromPrimitive + FloatConst> Dir<T> for SBend<L, T> {
+    fn start_ang(&self) -> Angle<T> {
+        self.ang_at(T::zero())
+    }
+    fn end_ang(&self) -> Angle<T> {
+        self.ang_at(T::one())
+    }
+}
+
+impl<L: LengthType, T: Num + Float + FromPrimitive + FloatConst> Pos<Length<L, T>>
+    for ArchimedeanSpiral<L, T>
+{
+    fn start_pos(&self) -> Coordinate<Length<L, T>> {
+        self.point_at(self.angle.0.to_rad())
+    }
+    fn end_pos(&self) -> Coordinate<Length<L, T>> {
+        self.point_at(self.angle.1.to_rad())
+    }
+}
+
+impl<L: LengthType, T: Num + Float + FromPrimitive + FloatConst> Dir<T>
+    for ArchimedeanSpiral<L, T>
+{
+    fn start_ang(&self) -> Angle<T> {
+        self.ang_at(self.angle.0.to_rad())
+    }
+    fn end_ang(&self) -> Angle<T> {
+        self.ang_at(self.angle.1.to_rad())
+    }
+}
+
+impl<L: LengthType, T: Num + Float + FromPrimitive + FloatConst> Pos<Length<L, T>>
+    for DoubleSpiral<L, T>
+{
+    fn start_pos(&self) -> Coordinate<Length<L, T>> {
+        self.start().0
+    }
+    fn end_pos(&self) -> Coordinate<Length<L, T>> {
+        self.end().0
+    }
+}
+
+impl<L: LengthType, T: Num + Float + FromPrimitive + FloatConst> Dir<T> for DoubleSpiral<L, T> {
+    fn start_ang(&self) -> Angle<T> {
+        self.start().1
+    }
+    fn end_ang(&self) -> Angle<T> {
+        self.end().1
+    }
+}
+
+impl<F, D, L, T> Pos<Length<L, T>> for ParametricCurve<F, D, L, T>
+where
+    F: Fn(T) -> LenCo<L, T>,
+    D: Fn(T) -> LenCo<L, T>,
+    L: LengthType,
+    T: Num + Float + FromPrimitive,
+{
+    fn start_pos(&self) -> Coordinate<Length<L, T>> {
+        self.point_at(self.range.0)
+    }
+    fn end_pos(&self) -> Coordinate<Length<L, T>> {
+        self.point_at(self.range.1)
+    }
+}
+
+impl<F, D, L, T> Dir<T> for ParametricCurve<F, D, L, T>
+where
+    F: Fn(T) -> LenCo<L, T>,
+    D: Fn(T) -> LenCo<L, T>,
+    L: LengthType,
+    T: Num + Float + FromPrimitive,
+{
+    fn start_ang(&self) -> Angle<T> {
+        self.ang_at(self.range.0)
+    }
+    fn end_ang(&self) -> Angle<T> {
+        self.ang_at(self.range.1)
+    }
+}
+
 impl<L: LengthType, T: Num + Float> Pos<Length<L, T>> for Line<L, T> {
     fn start_pos(&self) -> Coordinate<Length<L, T>> {
         self.start
@@ -430,72 +1334,147 @@ where
     }
 }
 
+//a curve swept with every band of a profile, the edges are given to the left of the path
 #[derive(Clone, Copy, Debug)]
-pub struct ArcCurve<W: AsRef<[Length<Absolute, f64>]>> {
-    arc: CircularArc,
+pub struct SweptCurve<C, W, L = Absolute, T = f64>
+where
+    W: Profile<Length<L, T>>,
+    L: LengthType,
+    T: Num,
+{
+    curve: C,
     width: W,
+    marker: PhantomData<Length<L, T>>,
 }
 
-impl<W: AsRef<[Length<Absolute, f64>]>> ArcCurve<W> {
-    pub fn new(arc: CircularArc, width: W) -> Self {
-        Self { arc, width }
-    }
-    pub fn rev(mut self) -> Self {
-        mem::swap(&mut self.arc.angle.0, &mut self.arc.angle.1);
-        self
+pub type ArcCurve<W, L = Absolute, T = f64> = SweptCurve<CircularArc<L, T>, W, L, T>;
+pub type EulerCurve<W, L = Absolute, T = f64> = SweptCurve<EulerBend<L, T>, W, L, T>;
+pub type SBendCurve<W, L = Absolute, T = f64> = SweptCurve<SBend<L, T>, W, L, T>;
+
+impl<C, W, L, T> SweptCurve<C, W, L, T>
+where
+    C: Sweep<Length<L, T>> + LeftBias<Length<L, T>> + Pos<Length<L, T>> + Dir<T> + Copy,
+    W: Profile<Length<L, T>>,
+    L: LengthType,
+    T: Num + Float + FloatConst + FromPrimitive + RealField,
+{
+    pub fn new(curve: C, width: W) -> Self {
+        Self {
+            curve,
+            width,
+            marker: PhantomData,
+        }
     }
     pub fn into_group(
         self,
-    ) -> Group<
-        Area<
-            impl Pos<Length<Absolute, f64>> + Dir<f64> + IntoIterator<Item = LenCo<Absolute, f64>>,
-        >,
-    > {
+    ) -> Group<Area<impl Pos<Length<L, T>> + Dir<T> + IntoIterator<Item = LenCo<L, T>>>> {
+        let curve = self.curve;
         Group(
             self.width
-                .as_ref()
+                .edges()
                 .into_iter()
-                .map(|x| LocatIter::locat_sweep(self.arc, (-*x / 2., *x / 2.)))
+                .map(|(r, l)| {
+                    LocatIter::locat_sweep(curve, (curve.left_bias(r), curve.left_bias(l)))
+                })
                 .collect(),
         )
     }
-    pub fn arc_mut(&mut self) -> &mut CircularArc {
-        &mut self.arc
+    pub fn curve_mut(&mut self) -> &mut C {
+        &mut self.curve
     }
 }
 
-impl<W: AsRef<[Length<Absolute, f64>]>> Pos<Length<Absolute, f64>> for ArcCurve<W> {
-    fn start_pos(&self) -> Coordinate<Length<Absolute, f64>> {
-        self.arc.start_pos()
+impl<W, L, T> ArcCurve<W, L, T>
+where
+    W: Profile<Length<L, T>>,
+    L: LengthType,
+    T: Num,
+{
+    pub fn rev(mut self) -> Self {
+        mem::swap(&mut self.curve.angle.0, &mut self.curve.angle.1);
+        self
     }
-    fn end_pos(&self) -> Coordinate<Length<Absolute, f64>> {
-        self.arc.end_pos()
+    pub fn arc_mut(&mut self) -> &mut CircularArc<L, T> {
+        &mut self.curve
     }
 }
 
-impl<W: AsRef<[Length<Absolute, f64>]>> Pos<Length<Absolute, f64>> for Rect<W> {
-    fn start_pos(&self) -> Coordinate<Length<Absolute, f64>> {
-        self.line.start_pos()
+impl<W, L, T> EulerCurve<W, L, T>
+where
+    W: Profile<Length<L, T>>,
+    L: LengthType,
+    T: Num,
+{
+    pub fn bend_mut(&mut self) -> &mut EulerBend<L, T> {
+        &mut self.curve
     }
-    fn end_pos(&self) -> Coordinate<Length<Absolute, f64>> {
-        self.line.end_pos()
+}
+
+impl<W, L, T> SBendCurve<W, L, T>
+where
+    W: Profile<Length<L, T>>,
+    L: LengthType,
+    T: Num,
+{
+    pub fn bend_mut(&mut self) -> &mut SBend<L, T> {
+        &mut self.curve
+    }
+}
+
+impl<C, W, L, T> Pos<Length<L, T>> for SweptCurve<C, W, L, T>
+where
+    C: Pos<Length<L, T>>,
+    W: Profile<Length<L, T>>,
+    L: LengthType,
+    T: Num,
+{
+    fn start_pos(&self) -> Coordinate<Length<L, T>> {
+        self.curve.start_pos()
+    }
+    fn end_pos(&self) -> Coordinate<Length<L, T>> {
+        self.curve.end_pos()
+    }
+}
+
+impl<C, W, L, T> Dir<T> for SweptCurve<C, W, L, T>
+where
+    C: Dir<T>,
+    W: Profile<Length<L, T>>,
+    L: LengthType,
+    T: Num,
+{
+    fn start_ang(&self) -> Angle<T> {
+        self.curve.start_ang()
+    }
+    fn end_ang(&self) -> Angle<T> {
+        self.curve.end_ang()
     }
 }
 
-impl<W: AsRef<[Length<Absolute, f64>]>> Dir<f64> for ArcCurve<W> {
-    fn start_ang(&self) -> Angle<f64> {
-        self.arc.start_ang()
+impl<W, L, T> Pos<Length<L, T>> for Rect<W, L, T>
+where
+    W: Profile<Length<L, T>>,
+    L: LengthType,
+    T: Num + Float + FloatConst + FromPrimitive + RealField,
+{
+    fn start_pos(&self) -> Coordinate<Length<L, T>> {
+        self.line.start_pos()
     }
-    fn end_ang(&self) -> Angle<f64> {
-        self.arc.end_ang()
+    fn end_pos(&self) -> Coordinate<Length<L, T>> {
+        self.line.end_pos()
     }
 }
 
-impl<W: AsRef<[Length<Absolute, f64>]>> Dir<f64> for Rect<W> {
-    fn start_ang(&self) -> Angle<f64> {
+impl<W, L, T> Dir<T> for Rect<W, L, T>
+where
+    W: Profile<Length<L, T>>,
+    L: LengthType,
+    T: Num + Float + FloatConst + FromPrimitive + RealField,
+{
+    fn start_ang(&self) -> Angle<T> {
         self.line.start_ang()
     }
-    fn end_ang(&self) -> Angle<f64> {
+    fn end_ang(&self) -> Angle<T> {
         self.line.end_ang()
     }
 }
@@ -575,16 +1554,26 @@ impl<Q: Quantity, A: Num, I: Iterator<Item = Coordinate<Q>>> Dir<A> for LocatIte
 }
 
 #[derive(Clone, Copy, Debug)]
-pub struct Rect<W: AsRef<[Length<Absolute, f64>]>> {
-    line: Line,
+pub struct Rect<W, L = Absolute, T = f64>
+where
+    W: Profile<Length<L, T>>,
+    L: LengthType,
+    T: Num,
+{
+    line: Line<L, T>,
     width: W,
 }
 
-impl<W: AsRef<[Length<Absolute, f64>]>> Rect<W> {
-    pub fn new(line: Line, width: W) -> Self {
+impl<W, L, T> Rect<W, L, T>
+where
+    W: Profile<Length<L, T>>,
+    L: LengthType,
+    T: Num + Float + FloatConst + FromPrimitive + RealField,
+{
+    pub fn new(line: Line<L, T>, width: W) -> Self {
         Self { line, width }
     }
-    pub fn from_length(length: Length<Absolute, f64>, width: W) -> Self {
+    pub fn from_length(length: Length<L, T>, width: W) -> Self {
         Self {
             line: Line {
                 start: [zero(), zero()].into(),
@@ -595,29 +1584,74 @@ impl<W: AsRef<[Length<Absolute, f64>]>> Rect<W> {
     }
     pub fn into_group(
         self,
-    ) -> Group<
-        Area<
-            impl Pos<Length<Absolute, f64>> + Dir<f64> + IntoIterator<Item = LenCo<Absolute, f64>>,
-        >,
-    > {
+    ) -> Group<Area<impl Pos<Length<L, T>> + Dir<T> + IntoIterator<Item = LenCo<L, T>>>> {
         Group(
             self.width
-                .as_ref()
+                .edges()
                 .into_iter()
-                .map(|x| LocatIter::locat_sweep(self.line, (-*x / 2., *x / 2.)))
+                .map(|e| LocatIter::locat_sweep(self.line, e))
                 .collect(),
         )
     }
-    pub fn line_mut(&mut self) -> &mut Line {
+    pub fn line_mut(&mut self) -> &mut Line<L, T> {
         &mut self.line
     }
 }
 
+impl<C, W, L, T> Bounded<Length<L, T>> for SweptCurve<C, W, L, T>
+where
+    C: Sweep<Length<L, T>> + LeftBias<Length<L, T>> + Pos<Length<L, T>> + Dir<T> + Copy,
+    W: Profile<Length<L, T>> + Clone,
+    L: LengthType,
+    T: Num + Float + FloatConst + FromPrimitive + RealField,
+{
+    fn bounding_box(&mut self) -> Option<BoundingBox<Length<L, T>>> {
+        BoundingBox::from_points(self.clone().into_group())
+    }
+}
+
+impl<W, L, T> Bounded<Length<L, T>> for Rect<W, L, T>
+where
+    W: Profile<Length<L, T>> + Clone,
+    L: LengthType,
+    T: Num + Float + FloatConst + FromPrimitive + RealField,
+{
+    fn bounding_box(&mut self) -> Option<BoundingBox<Length<L, T>>> {
+        BoundingBox::from_points(self.clone().into_group())
+    }
+}
+
+impl<Cell, C, L, T> Bounded<Length<L, T>> for CellCursor<Cell, C, L, T>
+where
+    Cell: AsMut<DgirCell<Length<L, T>>>,
+    C: Colour,
+    L: LengthType,
+    T: Num + Float + FloatConst + FromPrimitive,
+{
+    fn bounding_box(&mut self) -> Option<BoundingBox<Length<L, T>>> {
+        self.mut_cell().bounding_box()
+    }
+}
+
+impl<Cell, W, L, T> Bounded<Length<L, T>> for Assembler<Cell, W, L, T>
+where
+    Cell: AsMut<DgirCell<Length<L, T>>>,
+    W: Profile<Length<L, T>>,
+    L: LengthType,
+    T: Num + Float + FloatConst + FromPrimitive,
+{
+    fn bounding_box(&mut self) -> Option<BoundingBox<Length<L, T>>> {
+        self.flush();
+        self.mut_cell().bounding_box()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
         draw::{Resolution, APPROX_EQ_MARGIN},
+        units::AbsoluteLength,
         zero, MICROMETER, NANOMETER,
     };
     use float_cmp::ApproxEq;
@@ -633,6 +1667,10 @@ mod tests {
             .pos
             .approx_eq([zero(), MICROMETER * (2.).sqrt(),].into(), APPROX_EQ_MARGIN));
         assert!(c.dir.approx_eq(Angle::from_deg(90f64), APPROX_EQ_MARGIN));
+        assert!(c
+            .length
+            .straight
+            .approx_eq(MICROMETER * (2.).sqrt(), APPROX_EQ_MARGIN));
         let expected: [Coordinate<_>; 4] = [
             [MICROMETER / 4., zero()].into(),
             [MICROMETER / 4., MICROMETER * (2.).sqrt()].into(),
@@ -663,6 +1701,337 @@ mod tests {
                     radius * Angle::from_deg(30.).sin()
                 ]),
             APPROX_EQ_MARGIN
-        ))
+        ));
+        assert!(c
+            .length
+            .bend
+            .approx_eq(radius * std::f64::consts::FRAC_PI_6, APPROX_EQ_MARGIN));
+        assert!(c.length.total().approx_eq(
+            MICROMETER * (2.).sqrt() + radius * std::f64::consts::FRAC_PI_6,
+            APPROX_EQ_MARGIN
+        ));
+    }
+
+    #[test]
+    fn port_without_bands() {
+        let mut a: Assembler<_, _> = Assembler::new(
+            "empty",
+            [LayerData::new(1, 0)],
+            Vec::<AbsoluteLength<f64>>::new(),
+            Resolution::MinDistance(NANOMETER * 20.),
+        );
+        a.add_port("in");
+        assert!(a.into_cell().port("in").is_none());
+    }
+
+    #[test]
+    fn merge_segments() {
+        let route = |merge: bool| {
+            let mut a: Assembler<_, _> = Assembler::new(
+                "merge",
+                [LayerData::new(1, 0), LayerData::new(1, 1)],
+                [MICROMETER * 2., MICROMETER * 6.],
+                Resolution::MinDistance(NANOMETER * 20.),
+            );
+            a.set_merge(merge)
+                .extend(MICROMETER * 10.)
+                .turn(MICROMETER * 20., Angle::from_deg(90.))
+                .taper(MICROMETER * 10., [MICROMETER, MICROMETER * 4.])
+                .sbend(MICROMETER * 30., MICROMETER * 5.)
+                .extend(MICROMETER * 10.);
+            let b = a.bounding_box().unwrap();
+            (a.into_cell(), b)
+        };
+        let (merged, b) = route(true);
+        let (split, expected) = route(false);
+        assert_eq!(merged.elements.len(), 2);
+        assert_eq!(split.elements.len(), 10);
+        assert!(b.min().approx_eq(expected.min(), APPROX_EQ_MARGIN));
+        assert!(b.max().approx_eq(expected.max(), APPROX_EQ_MARGIN));
+
+        //a jump starts new polygons
+        let mut a: Assembler<_, _> = Assembler::new(
+            "merge",
+            [LayerData::new(1, 0)],
+            [MICROMETER],
+            Resolution::MinNumber(10),
+        );
+        a.set_merge(true)
+            .extend(MICROMETER)
+            .set_pos([zero(), MICROMETER * 5.])
+            .extend(MICROMETER);
+        assert_eq!(a.into_cell().elements.len(), 2);
+    }
+
+    #[test]
+    fn path_length() {
+        let mut a: Assembler<_, _> = Assembler::new(
+            "length",
+            [LayerData::new(1, 0)],
+            [MICROMETER],
+            Resolution::MinDistance(NANOMETER * 20.),
+        );
+        a.extend(MICROMETER * 10.)
+            .turn(MICROMETER * 10., Angle::from_deg(-90.))
+            .taper(MICROMETER * 5., [MICROMETER * 2.])
+            .sweep_in(ParametricCurve::new(
+                |t: f64| Coordinate::from([MICROMETER * 3. * t, MICROMETER * 4. * t]),
+                (0., 1.),
+                Resolution::MinNumber(10),
+            ));
+        let length = a.path_length();
+        assert_eq!(length.straight, MICROMETER * 10.);
+        assert_eq!(length.taper, MICROMETER * 5.);
+        assert!(length.bend.approx_eq(
+            MICROMETER * (5. * std::f64::consts::PI + 5.),
+            APPROX_EQ_MARGIN
+        ));
+        assert!(a
+            .reset_length()
+            .path_length()
+            .total()
+            .approx_eq(zero(), APPROX_EQ_MARGIN));
+    }
+
+    #[test]
+    fn branch_states() {
+        let mut a: Assembler<_, _> = Assembler::new(
+            "branch",
+            [LayerData::new(1, 0)],
+            [MICROMETER * 2.],
+            Resolution::MinDistance(NANOMETER * 20.),
+        );
+        a.extend(MICROMETER * 10.)
+            .push_state()
+            .turn(MICROMETER * 10., Angle::from_deg(90.));
+        let upper = a.cell_cur.cursor;
+        a.pop_state().turn(MICROMETER * 10., Angle::from_deg(-90.));
+        let lower = a.cell_cur.cursor;
+        assert!(upper.pos[0].approx_eq(lower.pos[0], APPROX_EQ_MARGIN));
+        assert!(upper.pos[1].approx_eq(-lower.pos[1], APPROX_EQ_MARGIN));
+
+        //a bookmark brings back the width and the colour as well
+        a.bookmark("tap");
+        a.width = [MICROMETER * 4.];
+        a.cell_cur.color = Group::from([LayerData::new(2, 0)]);
+        a.set_mirrored(true).extend(MICROMETER * 10.).goto("tap");
+        assert_eq!(a.width, [MICROMETER * 2.]);
+        assert_eq!(a.cell_cur.color.0, vec![LayerData::new(1, 0)]);
+        assert!(!a.cell_cur.mirrored());
+        assert!(a.cell_cur.cursor.pos.approx_eq(lower.pos, APPROX_EQ_MARGIN));
+        //nothing left to pop, the cursor stays
+        a.extend(MICROMETER).pop_state();
+        assert!(a.cell_cur.cursor.pos[1].approx_eq(lower.pos[1] - MICROMETER, APPROX_EQ_MARGIN));
+    }
+
+    #[test]
+    fn cross_section() {
+        use crate::{cross_section::Band, gds::Element};
+        let (core, slab, heater) = (
+            LayerData::new(1, 0),
+            LayerData::new(2, 0),
+            LayerData::new(3, 0),
+        );
+        let rib = CrossSection::new(vec![
+            Band::new(core, MICROMETER * 0.5, zero()),
+            Band::new(slab, MICROMETER * 3., zero())
+                .with_end_taper(MICROMETER * 10., MICROMETER * 0.5),
+            Band::new(heater, MICROMETER, MICROMETER * 2.),
+        ]);
+        //the colour is not used, the bands carry their own layers
+        let assembler = || -> Assembler<_, _> {
+            Assembler::new(
+                "rib",
+                [LayerData::new(9, 0)],
+                rib.clone(),
+                Resolution::MinDistance(NANOMETER * 20.),
+            )
+        };
+        let mut a = assembler();
+        a.cap_start().extend(MICROMETER * 10.);
+        let b = a.bounding_box().unwrap();
+        assert!(b.min().approx_eq(
+            [-MICROMETER * 10., -MICROMETER * 1.5].into(),
+            APPROX_EQ_MARGIN
+        ));
+        assert!(b.max().approx_eq(
+            [MICROMETER * 10., MICROMETER * 2.5].into(),
+            APPROX_EQ_MARGIN
+        ));
+        a.turn(MICROMETER * 10., Angle::from_deg(90.)).cap_end();
+        let cell = a.into_cell();
+        let layers: Vec<_> = cell
+            .elements
+            .iter()
+            .filter_map(|e| match e {
+                Element::Polygon(p) => Some(p.color),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            layers,
+            vec![slab, core, slab, heater, core, slab, heater, slab]
+        );
+        let mut merged = assembler();
+        merged
+            .set_merge(true)
+            .cap_start()
+            .extend(MICROMETER * 10.)
+            .turn(MICROMETER * 10., Angle::from_deg(90.))
+            .cap_end();
+        assert_eq!(merged.into_cell().elements.len(), 5);
+    }
+
+    #[test]
+    fn taper_profiles() {
+        use crate::gds::Element;
+        let outline = |shape: Option<TaperShape>| {
+            let mut a: Assembler<_, _> = Assembler::new(
+                "taper",
+                [LayerData::new(1, 0)],
+                [MICROMETER],
+                Resolution::MaxDeviation(NANOMETER),
+            );
+            match shape {
+                Some(shape) => a.taper_with(shape, MICROMETER * 100., [MICROMETER * 5.]),
+                None => a.taper_by(MICROMETER * 100., [MICROMETER * 5.], |t| t * t),
+            };
+            assert!(a.cell_cur.cursor.pos[0].approx_eq(MICROMETER * 100., APPROX_EQ_MARGIN));
+            match a.into_cell().elements.pop() {
+                Some(Element::Polygon(p)) => p.area.collect::<Vec<_>>(),
+                _ => unreachable!(),
+            }
+        };
+        assert_eq!(outline(Some(TaperShape::Linear)).len(), 5);
+        let check = |shape: Option<TaperShape>, width: fn(f64) -> f64| {
+            let points = outline(shape);
+            assert!(points.len() > 10);
+            //every point is on an edge, half the width from the centerline
+            assert!(points
+                .iter()
+                .all(|p| (p[1].value.abs() - width(p[0].value) / 2.).abs() < 1e-9));
+        };
+        check(Some(TaperShape::Exponential), |x| 5f64.powf(x / 100.));
+        check(Some(TaperShape::Parabolic), |x| {
+            (1. + 24. * x / 100.).sqrt()
+        });
+        check(None, |x| 1. + 4. * (x / 100.) * (x / 100.));
+    }
+
+    #[test]
+    fn tapered_bend() {
+        use crate::gds::Element;
+        let mut a: Assembler<_, _> = Assembler::new(
+            "tapered",
+            [LayerData::new(1, 0)],
+            [MICROMETER],
+            Resolution::MinDistance(NANOMETER * 20.),
+        );
+        a.set_merge(true).extend(MICROMETER * 10.).sweep_to(
+            CircularArc::new_origin(
+                MICROMETER * 10.,
+                (Angle::from_deg(-90.), Angle::from_deg(0.)),
+                Resolution::MinDistance(NANOMETER * 20.),
+            ),
+            [MICROMETER * 3.],
+        );
+        let cursor = a.cell_cur.cursor;
+        assert!(cursor.pos.approx_eq(
+            [MICROMETER * 20., MICROMETER * 10.].into(),
+            APPROX_EQ_MARGIN
+        ));
+        assert!(cursor.dir.approx_eq(Angle::from_deg(90.), APPROX_EQ_MARGIN));
+        a.turn(MICROMETER * 10., Angle::from_deg(90.))
+            .extend(MICROMETER * 10.);
+        assert_eq!(a.width, [MICROMETER * 3.]);
+        let mut cell = a.into_cell();
+        assert_eq!(cell.elements.len(), 1);
+        let points: Vec<_> = match cell.elements.pop() {
+            Some(Element::Polygon(p)) => p.area.map(|p| (p[0].value, p[1].value)).collect(),
+            _ => unreachable!(),
+        };
+        //the outline does not cross itself, the width grows linearly along the tapered bend
+        let area = points
+            .windows(2)
+            .map(|w| w[0].0 * w[1].1 - w[1].0 * w[0].1)
+            .sum::<f64>()
+            / 2.;
+        let expected = 10. + 25. * std::f64::consts::PI + 30.;
+        assert!((area.abs() - expected).abs() < expected * 1e-4);
+    }
+
+    #[test]
+    fn relative_f32() {
+        use crate::units::{DbUnit, Relative};
+        let db = |v: f32| Length::<Relative, f32>::new_relative::<DbUnit>(v);
+        let mut a: Assembler<_, _, Relative, f32> = Assembler::new(
+            "db",
+            [LayerData::new(1, 0)],
+            [db(500.)],
+            Resolution::MinNumber(65),
+        );
+        a.extend(db(1000.))
+            .turn(db(5000.), Angle::from_deg(90.))
+            .taper(db(2000.), [db(1000.)])
+            .euler_turn(db(5000.), Angle::from_deg(-90.), 0.5)
+            .sbend(db(4000.), db(1000.));
+        let cursor = a.cell_cur.cursor;
+        let euler = EulerBend::new(db(5000.), Angle::from_deg(-90.), 0.5, a.res).end_pos();
+        //the euler bend starts heading up
+        let x = 1000. + 5000. - euler[1].value + 4000.;
+        let y = 5000. + 2000. + euler[0].value + 1000.;
+        assert!(cursor.pos.approx_eq([db(x), db(y)].into(), (5e-2, 4)));
+        assert!(cursor.dir.approx_eq(Angle::from_deg(0.), (1e-5, 4)));
+        let length = a.path_length();
+        assert!((length.taper.value - 2000.).abs() < 1e-2);
+        assert_eq!(a.into_cell().elements.len(), 5);
+    }
+
+    #[test]
+    fn mirrored_path() {
+        use crate::gds::Element;
+        let draw = |mirrored: bool| {
+            let mut a: Assembler<_, _> = Assembler::new(
+                "mirrored",
+                [LayerData::new(1, 0)],
+                [MICROMETER],
+                Resolution::MinNumber(33),
+            );
+            a.set_mirrored(mirrored)
+                .extend(MICROMETER * 10.)
+                .turn(MICROMETER * 10., Angle::from_deg(90.))
+                .taper_with(TaperShape::Exponential, MICROMETER * 5., [MICROMETER * 2.])
+                .euler_turn(MICROMETER * 10., Angle::from_deg(-45.), 0.5)
+                .sbend(MICROMETER * 20., MICROMETER * 3.);
+            let cursor = a.cell_cur.cursor;
+            let polygons: Vec<Vec<_>> = a
+                .into_cell()
+                .elements
+                .into_iter()
+                .map(|e| match e {
+                    Element::Polygon(p) => p.area.collect(),
+                    _ => unreachable!(),
+                })
+                .collect();
+            (cursor, polygons)
+        };
+        let (cursor, polygons) = draw(false);
+        let (mirrored, reflected) = draw(true);
+        //started at the origin along x, the mirrored path is the reflection about the x axis
+        assert!(mirrored
+            .pos
+            .approx_eq([cursor.pos[0], -cursor.pos[1]].into(), APPROX_EQ_MARGIN));
+        assert!(mirrored
+            .dir
+            .approx_eq(Angle::from_rad(-cursor.dir.to_rad()), APPROX_EQ_MARGIN));
+        assert_eq!(polygons.len(), reflected.len());
+        for (p, r) in polygons.into_iter().zip(reflected) {
+            let p: Vec<_> = Area { area: p }.mirror_x().into_iter().collect();
+            assert_eq!(p.len(), r.len());
+            assert!(p
+                .into_iter()
+                .zip(r)
+                .all(|(a, b)| a.approx_eq(b, APPROX_EQ_MARGIN)));
+        }
     }
 }
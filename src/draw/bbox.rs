@@ -0,0 +1,190 @@
+use crate::{
+    units::{Length, LengthType},
+    Num, Quantity,
+};
+
+use super::coordinate::{Coordinate, LenCo};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox<Q: Quantity> {
+    pub(crate) min: Coordinate<Q>,
+    pub(crate) max: Coordinate<Q>,
+}
+
+//elements keep their points as iterators, so `&mut self` is needed to collect them in place
+pub trait Bounded<Q: Quantity> {
+    fn bounding_box(&mut self) -> Option<BoundingBox<Q>>;
+}
+
+fn partial_min<Q: PartialOrd>(a: Q, b: Q) -> Q {
+    if b < a {
+        b
+    } else {
+        a
+    }
+}
+
+fn partial_max<Q: PartialOrd>(a: Q, b: Q) -> Q {
+    if b > a {
+        b
+    } else {
+        a
+    }
+}
+
+impl<Q: Quantity> BoundingBox<Q> {
+    pub fn new<C: Into<Coordinate<Q>>>(a: C, b: C) -> Self {
+        let (a, b) = (a.into(), b.into());
+        Self {
+            min: Coordinate::from([
+                partial_min(a[0].clone(), b[0].clone()),
+                partial_min(a[1].clone(), b[1].clone()),
+            ]),
+            max: Coordinate::from([
+                partial_max(a[0].clone(), b[0].clone()),
+                partial_max(a[1].clone(), b[1].clone()),
+            ]),
+        }
+    }
+    pub fn from_points<I: IntoIterator<Item = Coordinate<Q>>>(points: I) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        Some(points.fold(Self::new(first.clone(), first), |b, p| b.include(p)))
+    }
+    pub fn min(&self) -> Coordinate<Q> {
+        self.min.clone()
+    }
+    pub fn max(&self) -> Coordinate<Q> {
+        self.max.clone()
+    }
+    //lower left, lower right, upper right and upper left
+    pub fn corners(&self) -> [Coordinate<Q>; 4] {
+        [
+            self.min.clone(),
+            Coordinate::from([self.max[0].clone(), self.min[1].clone()]),
+            self.max.clone(),
+            Coordinate::from([self.min[0].clone(), self.max[1].clone()]),
+        ]
+    }
+    pub fn include(self, p: Coordinate<Q>) -> Self {
+        self.union(Self::new(p.clone(), p))
+    }
+    pub fn union(self, other: Self) -> Self {
+        Self {
+            min: Coordinate::from([
+                partial_min(self.min[0].clone(), other.min[0].clone()),
+                partial_min(self.min[1].clone(), other.min[1].clone()),
+            ]),
+            max: Coordinate::from([
+                partial_max(self.max[0].clone(), other.max[0].clone()),
+                partial_max(self.max[1].clone(), other.max[1].clone()),
+            ]),
+        }
+    }
+    pub fn contains(&self, p: &Coordinate<Q>) -> bool {
+        self.min[0] <= p[0] && p[0] <= self.max[0] && self.min[1] <= p[1] && p[1] <= self.max[1]
+    }
+    pub(crate) fn merge(a: Option<Self>, b: Option<Self>) -> Option<Self> {
+        match (a, b) {
+            (Some(a), Some(b)) => Some(a.union(b)),
+            (a, None) => a,
+            (None, b) => b,
+        }
+    }
+}
+
+impl<L: LengthType, T: Num> BoundingBox<Length<L, T>> {
+    pub fn width(&self) -> Length<L, T> {
+        self.max[0] - self.min[0]
+    }
+    pub fn height(&self) -> Length<L, T> {
+        self.max[1] - self.min[1]
+    }
+    pub fn center(&self) -> LenCo<L, T> {
+        let two = T::one() + T::one();
+        Coordinate::from([
+            (self.min[0] + self.max[0]) / two,
+            (self.min[1] + self.max[1]) / two,
+        ])
+    }
+    //grows every side by `margin`
+    pub fn expand(self, margin: Length<L, T>) -> Self {
+        Self {
+            min: Coordinate::from([self.min[0] - margin, self.min[1] - margin]),
+            max: Coordinate::from([self.max[0] + margin, self.max[1] + margin]),
+        }
+    }
+    //box of the transformed corners
+    pub(crate) fn transform<F: FnMut(LenCo<L, T>) -> LenCo<L, T>>(self, f: F) -> Self {
+        Self::from_points(self.corners().into_iter().map(f)).unwrap()
+    }
+}
+
+impl<Q, C> Bounded<Q> for C
+where
+    Q: Quantity,
+    C: IntoIterator<Item = Coordinate<Q>> + Clone,
+{
+    fn bounding_box(&mut self) -> Option<BoundingBox<Q>> {
+        BoundingBox::from_points(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        draw::{
+            curve::{groups::Group, Area},
+            CircularArc, Line, Resolution,
+        },
+        units::Angle,
+        zero, MICROMETER,
+    };
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn bounding_boxes() {
+        let mut line = Line::new(
+            (MICROMETER * 3., -MICROMETER),
+            (-MICROMETER, MICROMETER * 2.),
+        );
+        let b = line.bounding_box().unwrap();
+        assert_eq!(b.min(), Coordinate::from((-MICROMETER, -MICROMETER)));
+        assert_eq!(
+            b.max(),
+            Coordinate::from((MICROMETER * 3., MICROMETER * 2.))
+        );
+        assert_eq!(b.width(), MICROMETER * 4.);
+        assert!(b.contains(&Coordinate::from((zero(), zero()))));
+
+        let mut arc = CircularArc::new_origin(
+            MICROMETER * 10.,
+            (Angle::from_deg(0.), Angle::from_deg(180.)),
+            Resolution::MinNumber(3),
+        );
+        let b = arc.bounding_box().unwrap();
+        assert!(b.height().approx_eq(MICROMETER * 10., (1e-12, 1)));
+        assert!(b.center()[1].approx_eq(MICROMETER * 5., (1e-12, 1)));
+
+        let mut group = Group(vec![
+            Area {
+                area: vec![Coordinate::from((zero(), zero()))],
+            },
+            Area {
+                area: vec![Coordinate::from((MICROMETER * 5., MICROMETER * 6.))],
+            },
+        ]);
+        let b = group.bounding_box().unwrap().expand(MICROMETER);
+        assert_eq!(b.min(), Coordinate::from((-MICROMETER, -MICROMETER)));
+        assert_eq!(
+            b.max(),
+            Coordinate::from((MICROMETER * 6., MICROMETER * 7.))
+        );
+        assert!(
+            Group::<Area<Vec<LenCo<crate::units::Absolute, f64>>>>(vec![])
+                .bounding_box()
+                .is_none()
+        );
+    }
+}
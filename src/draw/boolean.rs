@@ -0,0 +1,686 @@
+use std::{collections::HashMap, marker::PhantomData};
+
+use log::warn;
+use num::Float;
+
+use crate::{
+    units::{Length, LengthType},
+    Num,
+};
+
+use super::{
+    coordinate::{Coordinate, LenCo},
+    curve::{
+        groups::{Compound, Group},
+        Area,
+    },
+};
+
+pub(crate) type Point<T> = (T, T);
+type Segment<T> = (Point<T>, Point<T>);
+type Key = (u64, u64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BooleanOp {
+    Union,
+    Difference,
+    Intersection,
+    Xor,
+}
+
+impl BooleanOp {
+    fn apply(self, a: bool, b: bool) -> bool {
+        match self {
+            BooleanOp::Union => a || b,
+            BooleanOp::Difference => a && !b,
+            BooleanOp::Intersection => a && b,
+            BooleanOp::Xor => a ^ b,
+        }
+    }
+}
+
+pub trait IntoPolygons<L: LengthType, T: Num> {
+    fn into_polygons(self) -> Vec<Vec<LenCo<L, T>>>;
+}
+
+impl<L, T, A> IntoPolygons<L, T> for Area<A>
+where
+    L: LengthType,
+    T: Num,
+    A: IntoIterator<Item = LenCo<L, T>>,
+{
+    fn into_polygons(self) -> Vec<Vec<LenCo<L, T>>> {
+        vec![self.area.into_iter().collect()]
+    }
+}
+
+impl<L, T, G> IntoPolygons<L, T> for Group<G>
+where
+    L: LengthType,
+    T: Num,
+    G: IntoPolygons<L, T>,
+{
+    fn into_polygons(self) -> Vec<Vec<LenCo<L, T>>> {
+        self.0.into_iter().flat_map(|x| x.into_polygons()).collect()
+    }
+}
+
+impl<L, T, T1, T2> IntoPolygons<L, T> for Compound<T1, T2>
+where
+    L: LengthType,
+    T: Num,
+    T1: IntoPolygons<L, T>,
+    T2: IntoPolygons<L, T>,
+{
+    fn into_polygons(self) -> Vec<Vec<LenCo<L, T>>> {
+        let mut ret = self.0.into_polygons();
+        ret.extend(self.1.into_polygons());
+        ret
+    }
+}
+
+pub type Polygons<L, T> = Group<Area<Vec<LenCo<L, T>>>>;
+
+//every operand is treated as the union of its polygons (nonzero rule), holes of the result are joined to their outer boundary by keyhole cuts
+pub trait Boolean<L, T>: IntoPolygons<L, T> + Sized
+where
+    L: LengthType,
+    T: Num + Float,
+{
+    fn boolean<B: IntoPolygons<L, T>>(self, other: B, op: BooleanOp) -> Polygons<L, T> {
+        from_points(boolean(
+            to_points(self.into_polygons()),
+            to_points(other.into_polygons()),
+            op,
+        ))
+    }
+    fn union<B: IntoPolygons<L, T>>(self, other: B) -> Polygons<L, T> {
+        self.boolean(other, BooleanOp::Union)
+    }
+    fn difference<B: IntoPolygons<L, T>>(self, other: B) -> Polygons<L, T> {
+        self.boolean(other, BooleanOp::Difference)
+    }
+    fn intersection<B: IntoPolygons<L, T>>(self, other: B) -> Polygons<L, T> {
+        self.boolean(other, BooleanOp::Intersection)
+    }
+    fn xor<B: IntoPolygons<L, T>>(self, other: B) -> Polygons<L, T> {
+        self.boolean(other, BooleanOp::Xor)
+    }
+    fn merge(self) -> Polygons<L, T> {
+        self.boolean(
+            Group::<Area<Vec<LenCo<L, T>>>>(Vec::new()),
+            BooleanOp::Union,
+        )
+    }
+}
+
+impl<L, T, U> Boolean<L, T> for U
+where
+    L: LengthType,
+    T: Num + Float,
+    U: IntoPolygons<L, T>,
+{
+}
+
+pub(crate) fn to_points<L: LengthType, T: Num>(p: Vec<Vec<LenCo<L, T>>>) -> Vec<Vec<Point<T>>> {
+    p.into_iter()
+        .map(|x| x.into_iter().map(|c| (c[0].value, c[1].value)).collect())
+        .collect()
+}
+
+pub(crate) fn from_points<L: LengthType, T: Num>(p: Vec<Vec<Point<T>>>) -> Polygons<L, T> {
+    let len = |value: T| Length {
+        value,
+        marker: PhantomData,
+    };
+    Group(
+        p.into_iter()
+            .map(|x| Area {
+                area: x
+                    .into_iter()
+                    .map(|(x, y)| Coordinate::from([len(x), len(y)]))
+                    .collect(),
+            })
+            .collect(),
+    )
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Edge<T> {
+    x0: T,
+    y0: T,
+    x1: T,
+    y1: T,
+    wind: (i32, i32),
+}
+
+impl<T: Float> Edge<T> {
+    fn y_at(&self, x: T) -> T {
+        if x == self.x0 {
+            self.y0
+        } else if x == self.x1 {
+            self.y1
+        } else {
+            self.y0 + (self.y1 - self.y0) * (x - self.x0) / (self.x1 - self.x0)
+        }
+    }
+}
+
+//vertical edges are dropped, they never change the winding number inside a slab
+fn push_edges<T: Float>(polygons: &[Vec<Point<T>>], owner: usize, edges: &mut Vec<Edge<T>>) {
+    for p in polygons {
+        let n = if p.len() >= 2 && p.first() == p.last() {
+            p.len() - 1
+        } else {
+            p.len()
+        };
+        if n < 3 {
+            continue;
+        }
+        for i in 0..n {
+            let (a, b) = (p[i], p[(i + 1) % n]);
+            let wind = |w: i32| if owner == 0 { (w, 0) } else { (0, w) };
+            if a.0 < b.0 {
+                edges.push(Edge {
+                    x0: a.0,
+                    y0: a.1,
+                    x1: b.0,
+                    y1: b.1,
+                    wind: wind(1),
+                });
+            } else if a.0 > b.0 {
+                edges.push(Edge {
+                    x0: b.0,
+                    y0: b.1,
+                    x1: a.0,
+                    y1: a.1,
+                    wind: wind(-1),
+                });
+            }
+        }
+    }
+}
+
+fn sort_floats<T: Float>(v: &mut [T]) {
+    v.sort_by(|a, b| a.partial_cmp(b).unwrap());
+}
+
+//x of the crossing of `e` and `f`, which starts at or after `e`
+fn crossing_x<T: Float>(e: &Edge<T>, f: &Edge<T>) -> Option<T> {
+    if f.x0 >= e.x1 {
+        return None;
+    }
+    let (lo, hi) = (f.x0, e.x1.min(f.x1));
+    let d_lo = e.y_at(lo) - f.y_at(lo);
+    let d_hi = e.y_at(hi) - f.y_at(hi);
+    if (d_lo > T::zero() && d_hi < T::zero()) || (d_lo < T::zero() && d_hi > T::zero()) {
+        let x = lo + (hi - lo) * d_lo / (d_lo - d_hi);
+        if x > lo && x < hi {
+            return Some(x);
+        }
+    }
+    None
+}
+
+//edges are already sorted by x0, they are put in the cells of a grid over their bounding box and only the pairs
+//sharing a cell are tested, a pair sharing several cells gives the same x every time
+fn crossing_xs<T: Float>(edges: &[Edge<T>], xs: &mut Vec<T>) {
+    let n = edges.len();
+    if n < 2 {
+        return;
+    }
+    let (mut x_min, mut x_max) = (edges[0].x0, edges[0].x1);
+    let (mut y_min, mut y_max) = (edges[0].y0, edges[0].y0);
+    for e in edges {
+        x_min = x_min.min(e.x0);
+        x_max = x_max.max(e.x1);
+        y_min = y_min.min(e.y0.min(e.y1));
+        y_max = y_max.max(e.y0.max(e.y1));
+    }
+    let size = ((n as f64).sqrt().ceil() as usize).clamp(1, 1024);
+    let cell = |v: T, min: T, max: T| {
+        if max <= min {
+            0
+        } else {
+            ((v - min) / (max - min) * T::from(size).unwrap())
+                .to_usize()
+                .unwrap_or(0)
+                .min(size - 1)
+        }
+    };
+    let mut grid: Vec<Vec<u32>> = vec![Vec::new(); size * size];
+    for (i, e) in edges.iter().enumerate() {
+        let (c0, c1) = (cell(e.x0, x_min, x_max), cell(e.x1, x_min, x_max));
+        let step = (x_max - x_min) / T::from(size).unwrap();
+        for c in c0..=c1 {
+            //rows reached within the column, one more on each side for rounding
+            let left = (x_min + step * T::from(c).unwrap()).max(e.x0);
+            let right = (x_min + step * T::from(c + 1).unwrap()).min(e.x1);
+            let (ya, yb) = (e.y_at(left), e.y_at(right));
+            let r0 = cell(ya.min(yb), y_min, y_max).saturating_sub(1);
+            let r1 = (cell(ya.max(yb), y_min, y_max) + 1).min(size - 1);
+            for r in r0..=r1 {
+                grid[c * size + r].push(i as u32);
+            }
+        }
+    }
+    for bucket in grid.iter() {
+        for (k, &i) in bucket.iter().enumerate() {
+            let e = &edges[i as usize];
+            for &j in bucket[k + 1..].iter() {
+                let f = &edges[j as usize];
+                //the same pair is always tested in the same order
+                let (e, f) = if i < j { (e, f) } else { (f, e) };
+                if e.y0.max(e.y1) < f.y0.min(f.y1) || f.y0.max(f.y1) < e.y0.min(e.y1) {
+                    continue;
+                }
+                if let Some(x) = crossing_x(e, f) {
+                    xs.push(x);
+                }
+            }
+        }
+    }
+}
+
+//y values closer than eps at the same stop are snapped to one value, so neighbouring slabs share vertices exactly
+struct Canon<T> {
+    clusters: Vec<(T, T)>,
+}
+
+impl<T: Float> Canon<T> {
+    fn new(mut ys: Vec<T>, eps: T) -> Self {
+        sort_floats(&mut ys);
+        let mut clusters: Vec<(T, T)> = Vec::new();
+        for y in ys {
+            match clusters.last_mut() {
+                Some(c) if y - c.1 <= eps => c.1 = y,
+                _ => clusters.push((y, y)),
+            }
+        }
+        Self { clusters }
+    }
+    fn get(&self, y: T) -> T {
+        let i = self.clusters.partition_point(|c| c.1 < y);
+        self.clusters[i.min(self.clusters.len() - 1)].0
+    }
+}
+
+fn covered<T: Float>(intervals: &[(T, T)], idx: &mut usize, y0: T, y1: T) -> bool {
+    while *idx < intervals.len() && intervals[*idx].1 <= y0 {
+        *idx += 1;
+    }
+    *idx < intervals.len() && intervals[*idx].0 <= y0 && intervals[*idx].1 >= y1
+}
+
+//boundary on the line x, `left` are the inside intervals of the slab on the left side, `right` those of the slab on the right side
+fn vertical_boundary<T: Float>(
+    x: T,
+    left: &[(T, T)],
+    right: &[(T, T)],
+    segments: &mut Vec<Segment<T>>,
+) {
+    let mut ys: Vec<T> = left
+        .iter()
+        .chain(right.iter())
+        .flat_map(|i| [i.0, i.1])
+        .collect();
+    sort_floats(&mut ys);
+    ys.dedup();
+    let (mut li, mut ri) = (0, 0);
+    for w in ys.windows(2) {
+        let in_left = covered(left, &mut li, w[0], w[1]);
+        let in_right = covered(right, &mut ri, w[0], w[1]);
+        if in_left && !in_right {
+            segments.push(((x, w[0]), (x, w[1])));
+        } else if in_right && !in_left {
+            segments.push(((x, w[1]), (x, w[0])));
+        }
+    }
+}
+
+//returns inside intervals at the left and right side of the slab, the edges are left in their order across the slab,
+//which the next slab mostly keeps, so sorting them again is cheap
+fn slab_boundary<T: Float>(
+    (xa, xb): (T, T),
+    edges: &mut [Edge<T>],
+    (ca, cb): (&Canon<T>, &Canon<T>),
+    op: BooleanOp,
+    segments: &mut Vec<Segment<T>>,
+) -> (Vec<Point<T>>, Vec<Point<T>>) {
+    let mut items: Vec<(T, T, Edge<T>)> = edges
+        .iter()
+        .map(|e| (ca.get(e.y_at(xa)), cb.get(e.y_at(xb)), *e))
+        .collect();
+    items.sort_by(|a, b| (a.0 + a.1, a.0).partial_cmp(&(b.0 + b.1, b.0)).unwrap());
+    for (e, item) in edges.iter_mut().zip(items.iter()) {
+        *e = item.2;
+    }
+    let (mut left, mut right) = (Vec::new(), Vec::new());
+    let mut wind = (0, 0);
+    let mut inside = false;
+    let mut start = (T::zero(), T::zero());
+    let mut i = 0;
+    while i < items.len() {
+        let (yl, yr) = (items[i].0, items[i].1);
+        while i < items.len() && items[i].0 == yl && items[i].1 == yr {
+            wind.0 += items[i].2.wind.0;
+            wind.1 += items[i].2.wind.1;
+            i += 1;
+        }
+        let now = op.apply(wind.0 != 0, wind.1 != 0);
+        if now != inside {
+            if now {
+                segments.push(((xa, yl), (xb, yr)));
+                start = (yl, yr);
+            } else {
+                segments.push(((xb, yr), (xa, yl)));
+                if start.0 < yl {
+                    left.push((start.0, yl));
+                }
+                if start.1 < yr {
+                    right.push((start.1, yr));
+                }
+            }
+            inside = now;
+        }
+    }
+    (left, right)
+}
+
+fn key<T: Num>(p: Point<T>) -> Key {
+    (
+        (p.0.to_f64().unwrap() + 0.).to_bits(),
+        (p.1.to_f64().unwrap() + 0.).to_bits(),
+    )
+}
+
+fn cross<T: Float>(o: Point<T>, a: Point<T>, b: Point<T>) -> T {
+    (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+}
+
+//chain directed segments into closed loops, the most counter-clockwise turn is taken at shared vertices
+fn chain<T: Float + Num>(segments: Vec<Segment<T>>) -> Vec<Vec<Point<T>>> {
+    let mut count: HashMap<(Key, Key), i32> = HashMap::new();
+    for s in segments.iter() {
+        *count.entry((key(s.0), key(s.1))).or_insert(0) += 1;
+    }
+    let segments: Vec<_> = segments
+        .into_iter()
+        .filter(|s| {
+            let rev = count.get(&(key(s.1), key(s.0))).copied().unwrap_or(0);
+            rev == 0
+        })
+        .collect();
+    let mut outgoing: HashMap<Key, Vec<usize>> = HashMap::new();
+    for (i, s) in segments.iter().enumerate() {
+        outgoing.entry(key(s.0)).or_default().push(i);
+    }
+    let mut used = vec![false; segments.len()];
+    let mut loops = Vec::new();
+    for first in 0..segments.len() {
+        if used[first] {
+            continue;
+        }
+        used[first] = true;
+        let start = key(segments[first].0);
+        let mut points = vec![segments[first].0];
+        let mut cur = first;
+        loop {
+            let (a, b) = segments[cur];
+            if key(b) == start {
+                break;
+            }
+            points.push(b);
+            let d_in = (b.0 - a.0, b.1 - a.1);
+            let next = outgoing[&key(b)]
+                .iter()
+                .copied()
+                .filter(|&i| !used[i])
+                .max_by(|&i, &j| {
+                    let turn = |k: usize| {
+                        let d = (segments[k].1 .0 - b.0, segments[k].1 .1 - b.1);
+                        (d_in.0 * d.1 - d_in.1 * d.0).atan2(d_in.0 * d.0 + d_in.1 * d.1)
+                    };
+                    turn(i).partial_cmp(&turn(j)).unwrap()
+                });
+            match next {
+                Some(n) => {
+                    used[n] = true;
+                    cur = n;
+                }
+                None => {
+                    warn!("boundary not closed at ({}, {})", b.0, b.1);
+                    break;
+                }
+            }
+        }
+        loops.push(points);
+    }
+    loops
+}
+
+fn remove_collinear<T: Float>(points: Vec<Point<T>>, eps: T) -> Vec<Point<T>> {
+    let collinear = |a: Point<T>, b: Point<T>, c: Point<T>| {
+        let len = ((c.0 - a.0).powi(2) + (c.1 - a.1).powi(2)).sqrt();
+        cross(a, b, c).abs() <= eps * len.max(eps)
+    };
+    let mut ret: Vec<Point<T>> = Vec::with_capacity(points.len());
+    for p in points {
+        while ret.len() >= 2 && collinear(ret[ret.len() - 2], ret[ret.len() - 1], p) {
+            ret.pop();
+        }
+        ret.push(p);
+    }
+    while ret.len() >= 3 && collinear(ret[ret.len() - 2], ret[ret.len() - 1], ret[0]) {
+        ret.pop();
+    }
+    while ret.len() >= 3 && collinear(ret[ret.len() - 1], ret[0], ret[1]) {
+        ret.remove(0);
+    }
+    ret
+}
+
+pub(crate) fn signed_area<T: Float>(points: &[Point<T>]) -> T {
+    let n = points.len();
+    (0..n).fold(T::zero(), |acc, i| {
+        let (a, b) = (points[i], points[(i + 1) % n]);
+        acc + (a.0 * b.1 - b.0 * a.1)
+    }) / (T::one() + T::one())
+}
+
+//holes are processed from the rightmost one, so the ray to +x never hits a hole not merged yet
+fn keyhole<T: Float + Num>(loops: Vec<Vec<Point<T>>>) -> Vec<Vec<Point<T>>> {
+    let (mut outers, mut holes): (Vec<_>, Vec<_>) =
+        loops.into_iter().partition(|l| signed_area(l) > T::zero());
+    let max_x = |l: &Vec<Point<T>>| {
+        (0..l.len())
+            .max_by(|&i, &j| l[i].0.partial_cmp(&l[j].0).unwrap())
+            .unwrap()
+    };
+    holes.sort_by(|a, b| b[max_x(b)].0.partial_cmp(&a[max_x(a)].0).unwrap());
+    for hole in holes {
+        let hi = max_x(&hole);
+        let hp = hole[hi];
+        let mut best: Option<(usize, usize, T)> = None;
+        for (oi, o) in outers.iter().enumerate() {
+            for j in 0..o.len() {
+                let (a, b) = (o[j], o[(j + 1) % o.len()]);
+                if (a.1 <= hp.1 && b.1 > hp.1) || (b.1 <= hp.1 && a.1 > hp.1) {
+                    let x = a.0 + (hp.1 - a.1) * (b.0 - a.0) / (b.1 - a.1);
+                    if x >= hp.0 && best.is_none_or(|b| x < b.2) {
+                        best = Some((oi, j, x));
+                    }
+                }
+            }
+        }
+        match best {
+            Some((oi, j, x)) => {
+                let o = &mut outers[oi];
+                let bridge = (x, hp.1);
+                let mut inserted = Vec::with_capacity(hole.len() + 3);
+                inserted.push(bridge);
+                inserted.extend(hole[hi..].iter().chain(hole[..hi].iter()));
+                inserted.push(hp);
+                inserted.push(bridge);
+                o.splice(j + 1..j + 1, inserted);
+            }
+            None => warn!("hole at ({}, {}) has no outer boundary", hp.0, hp.1),
+        }
+    }
+    outers
+}
+
+//open loops with material on the left side, outer boundaries are counter-clockwise and holes clockwise
+pub(crate) fn boolean_loops<T: Float + Num>(
+    subject: Vec<Vec<Point<T>>>,
+    clip: Vec<Vec<Point<T>>>,
+    op: BooleanOp,
+) -> Vec<Vec<Point<T>>> {
+    let max_abs = subject
+        .iter()
+        .chain(clip.iter())
+        .flatten()
+        .fold(T::zero(), |m, p| m.max(p.0.abs()).max(p.1.abs()));
+    let eps = max_abs * T::epsilon() * <T as num::NumCast>::from(64).unwrap();
+
+    let mut edges = Vec::new();
+    push_edges(&subject, 0, &mut edges);
+    push_edges(&clip, 1, &mut edges);
+    edges.sort_by(|a, b| a.x0.partial_cmp(&b.x0).unwrap());
+
+    let mut xs: Vec<T> = edges.iter().flat_map(|e| [e.x0, e.x1]).collect();
+    crossing_xs(&edges, &mut xs);
+    sort_floats(&mut xs);
+    xs.dedup();
+
+    let mut segments = Vec::new();
+    let mut next = 0;
+    let mut slab: Vec<Edge<T>> = Vec::new();
+    let mut canon_prev: Option<Canon<T>> = None;
+    let mut right_prev: Vec<(T, T)> = Vec::new();
+    for (k, &x) in xs.iter().enumerate() {
+        //edges in `slab[..spanning]` span the slab on the left of x, the rest start at x
+        let spanning = slab.len();
+        while next < edges.len() && edges[next].x0 <= x {
+            slab.push(edges[next]);
+            next += 1;
+        }
+        let canon = Canon::new(slab.iter().map(|e| e.y_at(x)).collect(), eps);
+        if let Some(ca) = canon_prev.take() {
+            let (left, right) = slab_boundary(
+                (xs[k - 1], x),
+                &mut slab[..spanning],
+                (&ca, &canon),
+                op,
+                &mut segments,
+            );
+            vertical_boundary(xs[k - 1], &right_prev, &left, &mut segments);
+            right_prev = right;
+        }
+        slab.retain(|e| e.x1 > x);
+        canon_prev = Some(canon);
+    }
+    if let Some(&x) = xs.last() {
+        vertical_boundary(x, &right_prev, &[], &mut segments);
+    }
+
+    chain(segments)
+        .into_iter()
+        .map(|l| remove_collinear(l, eps))
+        .filter(|l| l.len() >= 3)
+        .collect()
+}
+
+pub(crate) fn close_loops<T: Float + Num>(loops: Vec<Vec<Point<T>>>) -> Vec<Vec<Point<T>>> {
+    keyhole(loops)
+        .into_iter()
+        .map(|mut l| {
+            l.push(l[0]);
+            l
+        })
+        .collect()
+}
+
+pub(crate) fn boolean<T: Float + Num>(
+    subject: Vec<Vec<Point<T>>>,
+    clip: Vec<Vec<Point<T>>>,
+    op: BooleanOp,
+) -> Vec<Vec<Point<T>>> {
+    close_loops(boolean_loops(subject, clip, op))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MICROMETER;
+
+    fn square(x: f64, y: f64, size: f64) -> Area<Vec<LenCo<crate::units::Absolute, f64>>> {
+        Area {
+            area: [(x, y), (x + size, y), (x + size, y + size), (x, y + size)]
+                .into_iter()
+                .map(|(x, y)| Coordinate::from([MICROMETER * x, MICROMETER * y]))
+                .collect(),
+        }
+    }
+
+    fn area_of(g: Polygons<crate::units::Absolute, f64>) -> f64 {
+        g.0.into_iter()
+            .map(|a| {
+                signed_area(
+                    &a.area
+                        .into_iter()
+                        .map(|c| (c[0].value, c[1].value))
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .sum()
+    }
+
+    #[test]
+    fn boolean_squares() {
+        let (a, b) = (square(0., 0., 2.), square(1., 1., 2.));
+        assert!((area_of(a.clone().union(b.clone())) - 7.).abs() < 1e-9);
+        assert!((area_of(a.clone().intersection(b.clone())) - 1.).abs() < 1e-9);
+        assert!((area_of(a.clone().difference(b.clone())) - 3.).abs() < 1e-9);
+        assert!((area_of(a.xor(b)) - 6.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn merge_abutting() {
+        let merged = Group(vec![square(0., 0., 1.), square(1., 0., 1.)]).merge();
+        assert_eq!(merged.0.len(), 1);
+        assert_eq!(merged.0[0].area.len(), 5);
+        assert!((area_of(merged) - 2.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn merge_crossing_strips() {
+        //a grid of strips turned so that every edge crosses many others
+        let (sin, cos) = 0.5f64.sin_cos();
+        let strip = |(x0, y0): (f64, f64), (w, h): (f64, f64)| Area {
+            area: [(x0, y0), (x0 + w, y0), (x0 + w, y0 + h), (x0, y0 + h)]
+                .into_iter()
+                .map(|(x, y)| {
+                    Coordinate::from([
+                        MICROMETER * (x * cos - y * sin),
+                        MICROMETER * (x * sin + y * cos),
+                    ])
+                })
+                .collect::<Vec<_>>(),
+        };
+        let strips: Vec<_> = (0..10)
+            .flat_map(|i| {
+                let o = 2. * i as f64;
+                [strip((0., o), (20., 1.)), strip((o, 0.), (1., 20.))]
+            })
+            .collect();
+        assert!((area_of(Group(strips).merge()) - 300.).abs() < 1e-6);
+    }
+
+    #[test]
+    fn keyhole_difference() {
+        let cut = square(0., 0., 4.).difference(square(1., 1., 2.));
+        assert_eq!(cut.0.len(), 1);
+        assert!((area_of(cut) - 12.).abs() < 1e-9);
+    }
+}
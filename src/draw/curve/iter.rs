@@ -1,14 +1,20 @@
-use std::iter::{Fuse, FusedIterator, Rev};
+use std::{
+    iter::{Fuse, FusedIterator, Rev},
+    marker::PhantomData,
+};
+
+use num::Float;
 
 use crate::{
     color::LayerData,
     draw::coordinate::Coordinate,
-    gds::{Element, Path, Polygon},
-    Quantity,
+    gds::{Element, Path, PathType, Polygon},
+    units::{Length, LengthType},
+    Num, Quantity,
 };
 
 use super::groups::Compound;
-use super::{Area, Bias, Curve, IntoArea, IntoCurve, Sweep};
+use super::{Area, Bias, Curve, IntoArea, IntoCurve, Sweep, SweepBy};
 
 impl<Q, C> Curve<C>
 where
@@ -47,6 +53,7 @@ where
             curve: Box::new(self.curve.into_iter()),
             color,
             width: None,
+            path_type: PathType::Flush,
         }
         .into()
     }
@@ -59,6 +66,20 @@ where
             curve: Box::new(self.curve.into_iter()),
             color,
             width: Some(width),
+            path_type: PathType::Flush,
+        }
+        .into()
+    }
+
+    pub fn width_path_with(self, width: Q, path_type: PathType<Q>, color: LayerData) -> Element<Q>
+    where
+        C: 'static,
+    {
+        Path {
+            curve: Box::new(self.curve.into_iter()),
+            color,
+            width: Some(width),
+            path_type,
         }
         .into()
     }
@@ -143,6 +164,49 @@ mod tests {
             c2.len()
         );
     }
+
+    #[test]
+    fn sweep_by_width() {
+        use crate::{
+            draw::{CircularArc, Line, Resolution},
+            units::Angle,
+            zero, MICROMETER,
+        };
+        use float_cmp::ApproxEq;
+        let arc = CircularArc::new_origin(
+            MICROMETER * 10.,
+            (Angle::from_deg(-30.), Angle::from_deg(120.)),
+            Resolution::MinNumber(50),
+        );
+        //a constant range is the same as a bias to the left
+        let by: Vec<_> = arc.sweep_by(|_| (-MICROMETER, MICROMETER * 2.)).area;
+        let biased: Vec<_> = arc
+            .sweep((arc.left_bias(-MICROMETER), arc.left_bias(MICROMETER * 2.)))
+            .into_iter()
+            .collect();
+        assert_eq!(by.len(), biased.len());
+        assert!(by
+            .iter()
+            .zip(biased.iter())
+            .all(|(a, b)| a.approx_eq(*b, (1e-9, 1))));
+
+        let line = Line::new((MICROMETER, MICROMETER), (MICROMETER * 11., MICROMETER));
+        let tapered = line
+            .sweep_by(|t| (-MICROMETER * (1. + t), MICROMETER * (1. + t)))
+            .area;
+        let expected: [Coordinate<_>; 5] = [
+            [MICROMETER, zero()].into(),
+            [MICROMETER * 11., -MICROMETER].into(),
+            [MICROMETER * 11., MICROMETER * 3.].into(),
+            [MICROMETER, MICROMETER * 2.].into(),
+            [MICROMETER, zero()].into(),
+        ];
+        assert_eq!(tapered.len(), expected.len());
+        assert!(tapered
+            .iter()
+            .zip(expected)
+            .all(|(a, b)| a.approx_eq(b, (1e-9, 1))));
+    }
 }
 
 impl<Q, C> IntoIterator for Curve<C>
@@ -221,3 +285,79 @@ where
         }
     }
 }
+
+//the offsets are taken along the normals of the drawn points, a tangent is that of the circle through the point and
+//its neighbours, so points on an arc are moved exactly as by `CircularArc::left_bias`
+impl<C, L, T> SweepBy<Length<L, T>, T> for C
+where
+    L: LengthType,
+    T: Num + Float,
+    C: IntoIterator<Item = Coordinate<Length<L, T>>>,
+{
+    fn sweep_by<F: Fn(T) -> (Length<L, T>, Length<L, T>)>(
+        self,
+        range: F,
+    ) -> Area<Vec<Coordinate<Length<L, T>>>> {
+        let mut points: Vec<_> = self.into_iter().map(|p| (p[0].value, p[1].value)).collect();
+        //a repeated point has no direction
+        points.dedup();
+        let n = points.len();
+        if n < 2 {
+            return Area { area: Vec::new() };
+        }
+        let chord = |a: (T, T), b: (T, T)| (b.0 - a.0, b.1 - a.1);
+        let norm = |d: (T, T)| d.0.hypot(d.1);
+        let mut tangents = vec![chord(points[0], points[1]); n];
+        for i in 1..n - 1 {
+            let (d1, d2) = (
+                chord(points[i - 1], points[i]),
+                chord(points[i], points[i + 1]),
+            );
+            let (h1, h2) = (norm(d1), norm(d2));
+            tangents[i] = (
+                d1.0 * h2 / h1 + d2.0 * h1 / h2,
+                d1.1 * h2 / h1 + d2.1 * h1 / h2,
+            );
+        }
+        //the tangents at both ends of a chord are mirrored about it
+        let mirror = |t: (T, T), d: (T, T)| {
+            let (u, t) = (
+                (d.0 / norm(d), d.1 / norm(d)),
+                (t.0 / norm(t), t.1 / norm(t)),
+            );
+            let k = (t.0 * u.0 + t.1 * u.1) * (T::one() + T::one());
+            (u.0 * k - t.0, u.1 * k - t.1)
+        };
+        if n > 2 {
+            tangents[0] = mirror(tangents[1], chord(points[0], points[1]));
+            tangents[n - 1] = mirror(tangents[n - 2], chord(points[n - 2], points[n - 1]));
+        }
+        let mut length = vec![T::zero(); n];
+        for i in 1..n {
+            length[i] = length[i - 1] + norm(chord(points[i - 1], points[i]));
+        }
+        let total = length[n - 1];
+        let to_len = |v: T| Length {
+            value: v,
+            marker: PhantomData,
+        };
+        let (right, left): (Vec<_>, Vec<_>) = points
+            .iter()
+            .zip(tangents)
+            .zip(length)
+            .map(|((p, t), s)| {
+                let (nx, ny) = (-t.1 / norm(t), t.0 / norm(t));
+                let (r, l) = range(s / total);
+                let at = |b: Length<L, T>| {
+                    Coordinate::from([to_len(p.0 + nx * b.value), to_len(p.1 + ny * b.value)])
+                };
+                (at(r), at(l))
+            })
+            .unzip();
+        //closed like the outline of a constant sweep
+        let mut area = right;
+        area.extend(left.into_iter().rev());
+        area.push(area[0]);
+        Area { area }
+    }
+}
@@ -49,10 +49,22 @@ pub trait Sweep<Q: Quantity> {
     fn sweep(self, range: (Q, Q)) -> Area<Self::Output>;
 }
 
+//a sweep whose `(right, left)` offsets follow the fraction of the length from the start, from 0 to 1, the offsets
+//are positive to the left of the path, for a `CircularArc` it is the bias given by `left_bias`
+pub trait SweepBy<Q: Quantity, T> {
+    fn sweep_by<F: Fn(T) -> (Q, Q)>(self, range: F) -> Area<Vec<Coordinate<Q>>>;
+}
+
 pub trait Bias<Q> {
     fn bias(&mut self, b: Q);
 }
 
+//the bias that moves a curve `b` to the left of its direction of travel, which is `b` itself unless the curve is
+//biased along something else, like the radius of a `CircularArc`
+pub trait LeftBias<Q> {
+    fn left_bias(&self, b: Q) -> Q;
+}
+
 pub trait Split<P>: Sized {
     fn split(self, pos: P) -> (Self, Self);
 }
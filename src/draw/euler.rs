@@ -0,0 +1,300 @@
+use std::marker::PhantomData;
+
+use log::warn;
+use num::{traits::FloatConst, Float, FromPrimitive, Zero};
+
+use crate::{
+    units::{Absolute, Angle, Length, LengthType},
+    Num,
+};
+
+use super::{
+    coordinate::{Coordinate, LenCo},
+    curve::{Bias, LeftBias, Split, SplitHalf},
+    deviation_sections, Resolution,
+};
+
+//the curvature grows linearly from zero to `1/min_radius` in the leading euler section, stays constant in the
+//circular middle section and decreases back to zero in the trailing one, `p` is the fraction of the angle taken by
+//the two euler sections, `p = 0` is a circular arc and `p = 1` is a full clothoid bend
+#[derive(Debug, Clone, Copy)]
+pub struct EulerBend<L = Absolute, T = f64>
+where
+    L: LengthType,
+    T: Num,
+{
+    pub(crate) min_radius: Length<L, T>,
+    pub(crate) angle: Angle<T>,
+    pub(crate) p: T,
+    pub(crate) bias: Length<L, T>,
+    pub(crate) range: (Length<L, T>, Length<L, T>),
+    pub(crate) resolution: Resolution<Length<L, T>>,
+}
+
+impl<L, T> EulerBend<L, T>
+where
+    L: LengthType,
+    T: Num + Float + FloatConst + FromPrimitive,
+{
+    pub fn new(
+        min_radius: Length<L, T>,
+        angle: Angle<T>,
+        p: T,
+        resolution: Resolution<Length<L, T>>,
+    ) -> Self {
+        if min_radius.is_negative() {
+            warn!("a negative radius {} is set", min_radius);
+        }
+        let p = if p < T::zero() || p > T::one() {
+            warn!("euler fraction {} is out of [0, 1], clamped", p);
+            p.max(T::zero()).min(T::one())
+        } else {
+            p
+        };
+        let mut bend = Self {
+            min_radius,
+            angle,
+            p,
+            bias: Zero::zero(),
+            range: (Zero::zero(), Zero::zero()),
+            resolution,
+        };
+        bend.range.1 = bend.total_length();
+        bend
+    }
+    pub fn min_radius(&self) -> Length<L, T> {
+        self.min_radius
+    }
+    pub fn angle(&self) -> Angle<T> {
+        self.angle
+    }
+    pub fn p(&self) -> T {
+        self.p
+    }
+    //length of the part between the split positions
+    pub fn length(&self) -> Length<L, T> {
+        self.range.1 - self.range.0
+    }
+    //length of the whole bend
+    pub fn total_length(&self) -> Length<L, T> {
+        self.min_radius * self.angle.to_rad().abs() * (T::one() + self.p)
+    }
+    fn phi_at(&self, s: T) -> T {
+        let two = T::one() + T::one();
+        let (r, theta) = (self.min_radius.value, self.angle.to_rad().abs());
+        let total = r * theta * (T::one() + self.p);
+        let s0 = r * theta * self.p;
+        let phi = if s0.is_zero() || (s >= s0 && s <= total - s0) {
+            s0 / (two * r) + (s - s0) / r
+        } else if s < s0 {
+            s * s / (two * r * s0)
+        } else {
+            theta - (total - s) * (total - s) / (two * r * s0)
+        };
+        phi * self.angle.to_rad().signum()
+    }
+    //Simpson's rule on the unbiased curve, one double step for every 0.001 rad of turning
+    fn integrate(&self, (a, b): (T, T)) -> (T, T) {
+        let turning = (self.phi_at(b) - self.phi_at(a)).abs();
+        let n = (turning / T::from_f64(0.001).unwrap())
+            .ceil()
+            .to_usize()
+            .unwrap()
+            .max(1)
+            * 2;
+        let h = (b - a) / T::from_usize(n).unwrap();
+        let (mut x, mut y) = (T::zero(), T::zero());
+        for i in 0..=n {
+            let w = if i == 0 || i == n {
+                T::one()
+            } else if i % 2 == 1 {
+                T::from_u8(4).unwrap()
+            } else {
+                T::from_u8(2).unwrap()
+            };
+            let phi = self.phi_at(a + h * T::from_usize(i).unwrap());
+            x += w * phi.cos();
+            y += w * phi.sin();
+        }
+        let k = h / T::from_u8(3).unwrap();
+        (x * k, y * k)
+    }
+    fn biased(&self, s: T, (x, y): (T, T)) -> LenCo<L, T> {
+        let phi = self.phi_at(s);
+        let b = self.bias.value;
+        let to_len = |v: T| Length {
+            value: v,
+            marker: PhantomData,
+        };
+        Coordinate::from([to_len(x - b * phi.sin()), to_len(y + b * phi.cos())])
+    }
+    pub(crate) fn ang_at(&self, s: Length<L, T>) -> Angle<T> {
+        Angle::from_rad(self.phi_at(s.value))
+    }
+    pub(crate) fn point_at(&self, s: Length<L, T>) -> LenCo<L, T> {
+        self.biased(s.value, self.integrate((T::zero(), s.value)))
+    }
+    fn section_num(&self) -> usize {
+        let section_num = match self.resolution {
+            Resolution::MinNumber(n) => {
+                debug_assert!(n > 1);
+                n - 1
+            }
+            Resolution::MinDistance(d) => (self.length() / d).abs().to_usize().unwrap(),
+            //the middle section is the sharpest, the bias moves it closer to or away from the center
+            Resolution::MaxDeviation(e) => {
+                let r = self.min_radius.value;
+                let biased = r - self.bias.value * self.angle.to_rad().signum();
+                deviation_sections(self.length().value / r, biased, e.value)
+            }
+        };
+        section_num.max(1)
+    }
+}
+
+impl<L, T> IntoIterator for EulerBend<L, T>
+where
+    L: LengthType,
+    T: Num + Float + FloatConst + FromPrimitive,
+{
+    type IntoIter = std::vec::IntoIter<LenCo<L, T>>;
+    type Item = LenCo<L, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        let section_num = self.section_num();
+        let (a, b) = (self.range.0.value, self.range.1.value);
+        let step = (b - a) / T::from_usize(section_num).unwrap();
+        let mut s = a;
+        let mut pos = self.integrate((T::zero(), a));
+        let mut points = Vec::with_capacity(section_num + 1);
+        points.push(self.biased(s, pos));
+        for i in 1..=section_num {
+            let next = a + step * T::from_usize(i).unwrap();
+            let d = self.integrate((s, next));
+            pos = (pos.0 + d.0, pos.1 + d.1);
+            s = next;
+            points.push(self.biased(s, pos));
+        }
+        points.into_iter()
+    }
+}
+
+impl<L, T> Bias<Length<L, T>> for EulerBend<L, T>
+where
+    L: LengthType,
+    T: Num,
+{
+    fn bias(&mut self, b: Length<L, T>) {
+        self.bias += b;
+    }
+}
+
+impl<L, T> LeftBias<Length<L, T>> for EulerBend<L, T>
+where
+    L: LengthType,
+    T: Num,
+{
+    fn left_bias(&self, b: Length<L, T>) -> Length<L, T> {
+        b
+    }
+}
+
+impl<L, T> Split<Length<L, T>> for EulerBend<L, T>
+where
+    L: LengthType,
+    T: Num + Float + FloatConst + FromPrimitive,
+{
+    //`pos` is the length from the start of the whole bend
+    fn split(self, pos: Length<L, T>) -> (Self, Self) {
+        if pos < self.range.0 || pos > self.range.1 {
+            warn!(
+                "split position at {}, but the original bend start at {}, end at {}",
+                pos, self.range.0, self.range.1
+            );
+        }
+        let res = match self.resolution {
+            Resolution::MinDistance(d) => Resolution::MinDistance(d),
+            Resolution::MaxDeviation(e) => Resolution::MaxDeviation(e),
+            Resolution::MinNumber(n) => {
+                Resolution::MinDistance(self.length() / T::from_usize(n).unwrap())
+            }
+        };
+        (
+            Self {
+                range: (self.range.0, pos),
+                resolution: res,
+                ..self
+            },
+            Self {
+                range: (pos, self.range.1),
+                resolution: res,
+                ..self
+            },
+        )
+    }
+}
+
+impl<L, T> SplitHalf<Length<L, T>> for EulerBend<L, T>
+where
+    L: LengthType,
+    T: Num + Float + FloatConst + FromPrimitive,
+{
+    fn split_half(self) -> (Self, Self) {
+        self.split((self.range.0 + self.range.1) / T::from_u8(2).unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cursor::Dir, cursor::Pos, draw::CircularArc, MICROMETER, NANOMETER};
+    use float_cmp::ApproxEq;
+
+    const MARGIN: (f64, i64) = (1e-9, 1);
+
+    #[test]
+    fn euler_endpoints() {
+        let res = Resolution::MinDistance(NANOMETER * 20.);
+        let arc = CircularArc::new_origin(
+            MICROMETER * 10.,
+            (Angle::from_deg(-90.), Angle::from_deg(0.)),
+            res,
+        );
+        let circular = EulerBend::new(MICROMETER * 10., Angle::from_deg(90.), 0., res);
+        assert!((circular.end_pos() - circular.start_pos())
+            .approx_eq(arc.end_pos() - arc.start_pos(), MARGIN));
+
+        let full = EulerBend::new(MICROMETER * 10., Angle::from_deg(90.), 1., res);
+        assert!(full.length().approx_eq(circular.length() * 2., MARGIN));
+        assert!(full.end_ang().approx_eq(Angle::from_deg(90.), MARGIN));
+        let end = full.end_pos();
+        assert!(end[0].approx_eq(end[1], MARGIN));
+        let points: Vec<_> = full.into_iter().collect();
+        assert!(points[0].approx_eq(full.start_pos(), MARGIN));
+        assert!(points.last().unwrap().approx_eq(end, MARGIN));
+
+        let right = EulerBend::new(MICROMETER * 10., Angle::from_deg(-90.), 0.5, res);
+        assert!(right.end_ang().approx_eq(Angle::from_deg(-90.), MARGIN));
+        assert!(right.end_pos()[1].is_negative());
+    }
+
+    #[test]
+    fn split_euler() {
+        let mut bend = EulerBend::new(
+            MICROMETER * 10.,
+            Angle::from_deg(120.),
+            0.6,
+            Resolution::MinNumber(50),
+        );
+        bend.bias(MICROMETER);
+        let (lower, upper) = bend.split_half();
+        assert!(lower.start_pos().approx_eq(bend.start_pos(), MARGIN));
+        assert!(lower.end_pos().approx_eq(upper.start_pos(), MARGIN));
+        assert!(upper.end_pos().approx_eq(bend.end_pos(), MARGIN));
+        assert!(lower.end_ang().approx_eq(Angle::from_deg(60.), MARGIN));
+        assert!(upper
+            .into_iter()
+            .last()
+            .unwrap()
+            .approx_eq(bend.end_pos(), MARGIN));
+    }
+}
@@ -11,12 +11,19 @@ use crate::{
 
 use self::{
     coordinate::{Coordinate, LenCo},
-    curve::{Bias, Split, SplitHalf},
+    curve::{Bias, LeftBias, Split, SplitHalf},
 };
 
+pub mod bbox;
+pub mod boolean;
 pub mod coordinate;
 pub mod curve;
-pub(crate) mod transfer;
+pub mod euler;
+pub mod offset;
+pub mod parametric;
+pub mod sbend;
+pub mod spiral;
+pub mod transfer;
 
 #[cfg(test)]
 pub(crate) const APPROX_EQ_MARGIN: (f64, i64) = (0.000000000001, 1);
@@ -25,6 +32,29 @@ pub(crate) const APPROX_EQ_MARGIN: (f64, i64) = (0.000000000001, 1);
 pub enum Resolution<T = Length<Absolute, f64>> {
     MinDistance(T),
     MinNumber(usize),
+    //largest allowed distance between a chord and the curve it replaces
+    MaxDeviation(T),
+}
+
+//most sections a curve is drawn with, a deviation too small for the floats to resolve stops here
+pub(crate) const MAX_SECTIONS: usize = 1 << 20;
+
+//sections needed for an arc of `angle` with `radius` to keep the sagitta within `deviation`
+pub(crate) fn deviation_sections<T: Float>(angle: T, radius: T, deviation: T) -> usize {
+    assert!(
+        deviation > T::zero(),
+        "the maximum deviation has to be positive"
+    );
+    if angle.is_zero() {
+        return 0;
+    }
+    let ratio = (T::one() - deviation / radius.abs()).max(-T::one());
+    let step = (T::one() + T::one()) * ratio.acos();
+    (angle.abs() / step)
+        .ceil()
+        .to_usize()
+        .unwrap_or(MAX_SECTIONS)
+        .min(MAX_SECTIONS)
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -63,6 +93,9 @@ where
             Resolution::MinDistance(d) => (ang_range.abs() * (self.radius / d).abs())
                 .to_usize()
                 .unwrap(),
+            Resolution::MaxDeviation(e) => {
+                deviation_sections(ang_range, self.radius.value, e.value)
+            }
         };
         if section_num < 1 {
             section_num = 1;
@@ -137,6 +170,16 @@ where
     pub fn angle(&self) -> (Angle<T>, Angle<T>) {
         self.angle
     }
+    //the bias that moves the arc `b` to the left of the direction of travel like the other curves, the bias itself
+    //goes along the radius
+    pub fn left_bias(&self, b: Length<L, T>) -> Length<L, T> {
+        let ccw = self.angle.1.to_rad() >= self.angle.0.to_rad();
+        if ccw == self.inner.radius.is_negative() {
+            b
+        } else {
+            -b
+        }
+    }
 }
 
 impl<L, T> IntoIterator for CircularArc<L, T>
@@ -163,6 +206,16 @@ where
     }
 }
 
+impl<L, T> LeftBias<Length<L, T>> for CircularArc<L, T>
+where
+    L: LengthType,
+    T: Num + Float + FloatConst + FromPrimitive,
+{
+    fn left_bias(&self, b: Length<L, T>) -> Length<L, T> {
+        CircularArc::left_bias(self, b)
+    }
+}
+
 impl<L, T> Split<Angle<T>> for CircularArc<L, T>
 where
     L: LengthType,
@@ -178,6 +231,7 @@ where
         }
         let res = match self.resolution {
             Resolution::MinDistance(d) => Resolution::MinDistance(d),
+            Resolution::MaxDeviation(e) => Resolution::MaxDeviation(e),
             Resolution::MinNumber(n) => {
                 let min_dis: Length<L, T> = (self.inner.radius
                     * (self.angle.1 - self.angle.0).to_rad().abs())
@@ -347,6 +401,50 @@ mod tests {
         ),);
     }
     #[test]
+    fn bias_arc() {
+        let mut ccw = CircularArc::new_origin(
+            MILLIMETER,
+            (Angle::from_deg(0.), Angle::from_deg(90.)),
+            Resolution::MinNumber(8),
+        );
+        let mut cw = ccw;
+        cw.set_ang((Angle::from_deg(90.), Angle::from_deg(0.)));
+        //the bias goes along the radius whatever the direction
+        ccw.bias(MILLIMETER);
+        cw.bias(MILLIMETER);
+        assert_eq!(ccw.radius(), MILLIMETER * 2.);
+        assert_eq!(cw.radius(), MILLIMETER * 2.);
+        //the left of a counterclockwise arc is its center
+        assert_eq!(ccw.left_bias(MILLIMETER), -MILLIMETER);
+        assert_eq!(cw.left_bias(MILLIMETER), MILLIMETER);
+    }
+    #[test]
+    fn arc_max_deviation() {
+        let radius = MILLIMETER * 0.24;
+        let arc = CircularArc::new_origin(
+            radius,
+            (Angle::from_deg(0.), Angle::from_deg(360.)),
+            Resolution::MaxDeviation(MILLIMETER * 1e-6),
+        );
+        let points: Vec<_> = arc.into_iter().collect();
+        assert_eq!(points.len(), 1090);
+        assert!(points.windows(2).all(|w| {
+            let mid = (w[0].to_basic().0.coords + w[1].to_basic().0.coords) / 2.;
+            radius.value - mid.norm() <= 1e-3 + 1e-12
+        }));
+        let (lower, upper) = arc.split_half();
+        assert_eq!(lower.into_iter().count() + upper.into_iter().count(), 1092);
+    }
+    #[test]
+    fn deviation_limits() {
+        //a deviation below the float resolution of the radius takes the most sections, not the fewest
+        assert_eq!(deviation_sections(1f64, 1e6, 1e-12), MAX_SECTIONS);
+        assert_eq!(deviation_sections(0f64, 1e6, 1e-12), 0);
+        assert_eq!(deviation_sections(1f64, 1., 1e-3), 12);
+        assert!(std::panic::catch_unwind(|| deviation_sections(1f64, 1., 0.)).is_err());
+        assert!(std::panic::catch_unwind(|| deviation_sections(1f64, 1., -1e-3)).is_err());
+    }
+    #[test]
     fn split_line() {
         let line = Line::new(
             (MILLIMETER * 0., MILLIMETER),
@@ -0,0 +1,344 @@
+use num::{traits::FloatConst, Float, FromPrimitive};
+
+use crate::{
+    units::{Absolute, Length, LengthType},
+    Num,
+};
+
+use super::{
+    boolean::{
+        boolean_loops, close_loops, from_points, signed_area, to_points, BooleanOp, IntoPolygons,
+        Point, Polygons,
+    },
+    deviation_sections, Resolution,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JoinStyle<L: LengthType = Absolute, T: Num = f64> {
+    //miter length limit in multiples of the offset distance, longer corners are squared off at the limit
+    Miter(T),
+    Round(Resolution<Length<L, T>>),
+    Square,
+}
+
+//positive delta grows the polygons, negative delta shrinks them
+pub trait Offset<L, T>: IntoPolygons<L, T> + Sized
+where
+    L: LengthType,
+    T: Num + Float + FromPrimitive,
+{
+    fn offset(self, delta: Length<L, T>, join: JoinStyle<L, T>) -> Polygons<L, T> {
+        from_points(offset(to_points(self.into_polygons()), delta.value, join))
+    }
+}
+
+impl<L, T, U> Offset<L, T> for U
+where
+    L: LengthType,
+    T: Num + Float + FromPrimitive,
+    U: IntoPolygons<L, T>,
+{
+}
+
+fn add<T: Float>(a: Point<T>, b: Point<T>) -> Point<T> {
+    (a.0 + b.0, a.1 + b.1)
+}
+
+fn scale<T: Float>(a: Point<T>, s: T) -> Point<T> {
+    (a.0 * s, a.1 * s)
+}
+
+fn unit<T: Float>(a: Point<T>, b: Point<T>) -> Point<T> {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let len = dx.hypot(dy);
+    (dx / len, dy / len)
+}
+
+//corner filling the gap between the offset edges around vertex `v`, `n1` and `n2` are the offset vectors of the incoming and outgoing edge
+fn join_shape<L, T>(
+    v: Point<T>,
+    (e1, e2): (Point<T>, Point<T>),
+    (n1, n2): (Point<T>, Point<T>),
+    d: T,
+    join: JoinStyle<L, T>,
+) -> Option<Vec<Point<T>>>
+where
+    L: LengthType,
+    T: Num + Float + FromPrimitive,
+{
+    let two = T::one() + T::one();
+    let phi = (n1.0 * n2.1 - n1.1 * n2.0).atan2(n1.0 * n2.0 + n1.1 * n2.1);
+    let (half_sin, half_cos) = ((phi.abs() / two).sin(), (phi / two).cos());
+    if half_sin <= T::epsilon() {
+        return None;
+    }
+    let cut = |c: T| {
+        let t = (c - d * half_cos) / half_sin;
+        vec![
+            v,
+            add(v, n1),
+            add(add(v, n1), scale(e1, t)),
+            add(add(v, n2), scale(e2, -t)),
+            add(v, n2),
+        ]
+    };
+    Some(match join {
+        JoinStyle::Miter(limit) => {
+            if half_cos * limit >= T::one() {
+                let m = scale(add(n1, n2), T::one() / (two * half_cos * half_cos));
+                vec![v, add(v, n1), add(v, m), add(v, n2)]
+            } else {
+                cut(d * limit)
+            }
+        }
+        JoinStyle::Square => cut(d),
+        JoinStyle::Round(res) => {
+            let mut section_num = match res {
+                Resolution::MinNumber(n) => {
+                    debug_assert!(n > 1);
+                    n - 1
+                }
+                Resolution::MinDistance(dis) => (phi.abs() * d / dis.value).to_usize().unwrap(),
+                Resolution::MaxDeviation(e) => deviation_sections(phi, d, e.value),
+            };
+            if section_num < 1 {
+                section_num = 1;
+            }
+            let mut points = Vec::with_capacity(section_num + 2);
+            points.push(v);
+            points.extend((0..=section_num).map(|s| {
+                let ang = phi / T::from_usize(section_num).unwrap() * T::from_usize(s).unwrap();
+                add(
+                    v,
+                    (
+                        n1.0 * ang.cos() - n1.1 * ang.sin(),
+                        n1.0 * ang.sin() + n1.1 * ang.cos(),
+                    ),
+                )
+            }));
+            points
+        }
+    })
+}
+
+//pieces of the band merged together before the whole band is
+const BAND_CHUNK: usize = 32;
+
+//the polygons are merged first, then a band of edge rectangles and corner joins is added to or cut from them
+pub(crate) fn offset<L, T>(
+    polygons: Vec<Vec<Point<T>>>,
+    delta: T,
+    join: JoinStyle<L, T>,
+) -> Vec<Vec<Point<T>>>
+where
+    L: LengthType,
+    T: Num + Float + FromPrimitive,
+{
+    let material = boolean_loops(polygons, Vec::new(), BooleanOp::Union);
+    if delta.is_zero() {
+        return close_loops(material);
+    }
+    //material is on the left side of every loop, so the band lies on the right side when growing
+    let side = delta.signum();
+    let d = delta.abs();
+    let mut band = Vec::new();
+    let mut push_band = |mut p: Vec<Point<T>>| {
+        if signed_area(&p) < T::zero() {
+            p.reverse();
+        }
+        band.push(p);
+    };
+    for l in material.iter() {
+        let n = l.len();
+        for i in 0..n {
+            let (a, b, c) = (l[i], l[(i + 1) % n], l[(i + 2) % n]);
+            let (e1, e2) = (unit(a, b), unit(b, c));
+            let n1 = scale((e1.1, -e1.0), side * d);
+            let n2 = scale((e2.1, -e2.0), side * d);
+            push_band(vec![a, b, add(b, n1), add(a, n1)]);
+            if (e1.0 * e2.1 - e1.1 * e2.0) * side > T::zero() {
+                if let Some(j) = join_shape(b, (e1, e2), (n1, n2), d, join) {
+                    push_band(j);
+                }
+            }
+        }
+    }
+    //neighbouring pieces overlap a lot, merged in runs along the loops first they leave few edges to sweep at once
+    let band: Vec<_> = band
+        .chunks(BAND_CHUNK)
+        .flat_map(|c| boolean_loops(c.to_vec(), Vec::new(), BooleanOp::Union))
+        .collect();
+    if delta.is_positive() {
+        let mut subject = material;
+        subject.extend(band);
+        close_loops(boolean_loops(subject, Vec::new(), BooleanOp::Union))
+    } else {
+        close_loops(boolean_loops(material, band, BooleanOp::Difference))
+    }
+}
+
+//closed outline of a path with mitered joints, `extension` lengthens the begin and the end, round caps are added
+//if `round` is given
+pub(crate) fn path_outline<L, T>(
+    points: Vec<Point<T>>,
+    half_width: T,
+    (begin, end): (T, T),
+    round: Option<Resolution<Length<L, T>>>,
+) -> Vec<Point<T>>
+where
+    L: LengthType,
+    T: Num + Float + FloatConst + FromPrimitive,
+{
+    let mut points = points;
+    points.dedup();
+    let n = points.len();
+    if n < 2 {
+        return Vec::new();
+    }
+    let first = unit(points[1], points[0]);
+    points[0] = add(points[0], scale(first, begin));
+    let last = unit(points[n - 2], points[n - 1]);
+    points[n - 1] = add(points[n - 1], scale(last, end));
+    let normals: Vec<_> = points
+        .windows(2)
+        .map(|w| {
+            let e = unit(w[0], w[1]);
+            (-e.1, e.0)
+        })
+        .collect();
+    //`side` is 1 for the left edge and -1 for the right one
+    let edge = |side: T| {
+        let h = half_width * side;
+        let mut edge = vec![add(points[0], scale(normals[0], h))];
+        for i in 1..n - 1 {
+            let (n1, n2) = (normals[i - 1], normals[i]);
+            let cos = n1.0 * n2.0 + n1.1 * n2.1;
+            if T::one() + cos <= T::epsilon() {
+                //the path turns back on itself, the miter would be infinitely long
+                edge.push(add(points[i], scale(n1, h)));
+                edge.push(add(points[i], scale(n2, h)));
+            } else {
+                edge.push(add(points[i], scale(add(n1, n2), h / (T::one() + cos))));
+            }
+        }
+        edge.push(add(points[n - 1], scale(normals[n - 2], h)));
+        edge
+    };
+    //half circle around `center` from the `normal` side to the opposite one, turning clockwise
+    let cap = |center: Point<T>, normal: Point<T>, sections: usize| {
+        (1..sections).map(move |s| {
+            let ang = -T::PI() * T::from_usize(s).unwrap() / T::from_usize(sections).unwrap();
+            add(
+                center,
+                scale(
+                    (
+                        normal.0 * ang.cos() - normal.1 * ang.sin(),
+                        normal.0 * ang.sin() + normal.1 * ang.cos(),
+                    ),
+                    half_width,
+                ),
+            )
+        })
+    };
+    let round = round.map(|res| {
+        match res {
+            Resolution::MinNumber(n) => {
+                debug_assert!(n > 1);
+                n - 1
+            }
+            Resolution::MinDistance(d) => (T::PI() * half_width / d.value).to_usize().unwrap(),
+            Resolution::MaxDeviation(e) => deviation_sections(T::PI(), half_width, e.value),
+        }
+        .max(2)
+    });
+    let mut outline = edge(T::one());
+    if let Some(sections) = round {
+        outline.extend(cap(points[n - 1], normals[n - 2], sections));
+    }
+    outline.extend(edge(-T::one()).into_iter().rev());
+    if let Some(sections) = round {
+        let (x, y) = normals[0];
+        outline.extend(cap(points[0], (-x, -y), sections));
+    }
+    outline.push(outline[0]);
+    outline
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        draw::{
+            coordinate::{Coordinate, LenCo},
+            curve::Area,
+        },
+        MICROMETER, NANOMETER,
+    };
+
+    fn square(x: f64, y: f64, size: f64) -> Area<Vec<LenCo<Absolute, f64>>> {
+        Area {
+            area: [(x, y), (x + size, y), (x + size, y + size), (x, y + size)]
+                .into_iter()
+                .map(|(x, y)| Coordinate::from([MICROMETER * x, MICROMETER * y]))
+                .collect(),
+        }
+    }
+
+    fn area_of(g: Polygons<Absolute, f64>) -> f64 {
+        g.0.into_iter()
+            .map(|a| signed_area(&to_points(vec![a.area]).remove(0)))
+            .sum()
+    }
+
+    #[test]
+    fn grow_square() {
+        let miter = square(0., 0., 2.).offset(MICROMETER, JoinStyle::Miter(2.));
+        assert!((area_of(miter) - 16.).abs() < 1e-9);
+        let squared = square(0., 0., 2.).offset(MICROMETER, JoinStyle::Square);
+        assert!((area_of(squared) - (4. + 8. + 4. * (2. * 2f64.sqrt() - 2.))).abs() < 1e-9);
+        let round = square(0., 0., 2.).offset(
+            MICROMETER,
+            JoinStyle::Round(Resolution::MinDistance(NANOMETER)),
+        );
+        assert!((area_of(round) - (12. + std::f64::consts::PI)).abs() < 1e-5);
+    }
+
+    #[test]
+    fn shrink_with_hole() {
+        let frame = square(0., 0., 4.).offset(-MICROMETER, JoinStyle::Miter(2.));
+        assert!((area_of(frame) - 4.).abs() < 1e-9);
+        let holed =
+            crate::draw::boolean::Boolean::difference(square(0., 0., 4.), square(1., 1., 2.));
+        let shrunk = holed.offset(-MICROMETER / 4., JoinStyle::Miter(2.));
+        assert_eq!(shrunk.0.len(), 1);
+        assert!((area_of(shrunk) - 6.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn outline_path() {
+        let l_shape = vec![(0., 0.), (10., 0.), (10., 10.), (10., 10.)];
+        let flush = path_outline::<Absolute, f64>(l_shape.clone(), 1., (0., 0.), None);
+        assert_eq!(
+            flush,
+            vec![
+                (0., 1.),
+                (9., 1.),
+                (9., 10.),
+                (11., 10.),
+                (11., -1.),
+                (0., -1.),
+                (0., 1.)
+            ]
+        );
+        assert!((signed_area(&flush) + 40.).abs() < 1e-9);
+        let extended = path_outline::<Absolute, f64>(l_shape.clone(), 1., (1., 1.), None);
+        assert!((signed_area(&extended) + 44.).abs() < 1e-9);
+        let round = path_outline(
+            l_shape,
+            1.,
+            (0., 0.),
+            Some(Resolution::MinDistance(NANOMETER)),
+        );
+        assert!((signed_area(&round) + 40. + std::f64::consts::PI).abs() < 1e-5);
+    }
+}
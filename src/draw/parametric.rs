@@ -0,0 +1,312 @@
+use std::marker::PhantomData;
+
+use num::{Float, FromPrimitive, Zero};
+
+use crate::{
+    units::{Absolute, Angle, Length, LengthType},
+    Num,
+};
+
+use super::{
+    coordinate::{Coordinate, LenCo},
+    curve::Bias,
+    deviation_sections, Resolution,
+};
+
+//number of samples used to measure the arc length before resampling
+const TABLE_SIZE: usize = 1024;
+
+//curve given by `curve(t)` for `t` in `range`, the derivative is estimated by finite difference if not given
+#[derive(Clone, Copy)]
+pub struct ParametricCurve<F, D, L = Absolute, T = f64>
+where
+    L: LengthType,
+    T: Num,
+{
+    pub(crate) curve: F,
+    pub(crate) derivative: Option<D>,
+    pub(crate) range: (T, T),
+    pub(crate) bias: Length<L, T>,
+    pub(crate) resolution: Resolution<Length<L, T>>,
+}
+
+impl<F, L, T> ParametricCurve<F, fn(T) -> LenCo<L, T>, L, T>
+where
+    F: Fn(T) -> LenCo<L, T>,
+    L: LengthType,
+    T: Num + Float + FromPrimitive,
+{
+    pub fn new(curve: F, range: (T, T), resolution: Resolution<Length<L, T>>) -> Self {
+        Self {
+            curve,
+            derivative: None,
+            range,
+            bias: Zero::zero(),
+            resolution,
+        }
+    }
+}
+
+impl<F, D, L, T> ParametricCurve<F, D, L, T>
+where
+    F: Fn(T) -> LenCo<L, T>,
+    D: Fn(T) -> LenCo<L, T>,
+    L: LengthType,
+    T: Num + Float + FromPrimitive,
+{
+    pub fn with_derivative(
+        curve: F,
+        derivative: D,
+        range: (T, T),
+        resolution: Resolution<Length<L, T>>,
+    ) -> Self {
+        Self {
+            curve,
+            derivative: Some(derivative),
+            range,
+            bias: Zero::zero(),
+            resolution,
+        }
+    }
+    pub fn range(&self) -> (T, T) {
+        self.range
+    }
+    pub fn set_range(&mut self, range: (T, T)) -> &mut Self {
+        self.range = range;
+        self
+    }
+    pub fn length(&self) -> Length<L, T> {
+        Length {
+            value: self.length_table(TABLE_SIZE).last().unwrap().1,
+            marker: PhantomData,
+        }
+    }
+    //tangent in the direction of travel from `range.0` to `range.1`
+    fn tangent(&self, t: T) -> (T, T) {
+        let sign = (self.range.1 - self.range.0).signum();
+        let (dx, dy) = match &self.derivative {
+            Some(d) => {
+                let v = d(t);
+                (v[0].value, v[1].value)
+            }
+            None => {
+                //second order differences, one sided at the ends of the range
+                let (lo, hi) = (
+                    self.range.0.min(self.range.1),
+                    self.range.0.max(self.range.1),
+                );
+                let h = (hi - lo) * T::from_f64(1e-5).unwrap();
+                let at = |t: T| {
+                    let p = (self.curve)(t);
+                    (p[0].value, p[1].value)
+                };
+                //`(4 * f(t + k) - f(t + 2k) - 3 * f(t)) / 2k`
+                let one_sided = |k: T| {
+                    let (a, b, c) = (at(t + k), at(t + k + k), at(t));
+                    let (three, four) = (T::from_u8(3).unwrap(), T::from_u8(4).unwrap());
+                    (
+                        (a.0 * four - b.0 - c.0 * three) / (k + k),
+                        (a.1 * four - b.1 - c.1 * three) / (k + k),
+                    )
+                };
+                if t - h < lo {
+                    one_sided(h)
+                } else if t + h > hi {
+                    one_sided(-h)
+                } else {
+                    let (a, b) = (at(t + h), at(t - h));
+                    ((a.0 - b.0) / (h + h), (a.1 - b.1) / (h + h))
+                }
+            }
+        };
+        (dx * sign, dy * sign)
+    }
+    pub(crate) fn point_at(&self, t: T) -> LenCo<L, T> {
+        let p = (self.curve)(t);
+        if self.bias.value.is_zero() {
+            return p;
+        }
+        let (dx, dy) = self.tangent(t);
+        let k = self.bias.value / dx.hypot(dy);
+        let to_len = |v: T| Length {
+            value: v,
+            marker: PhantomData,
+        };
+        Coordinate::from([p[0] + to_len(-dy * k), p[1] + to_len(dx * k)])
+    }
+    pub(crate) fn ang_at(&self, t: T) -> Angle<T> {
+        let (dx, dy) = self.tangent(t);
+        Angle::from_rad(dy.atan2(dx))
+    }
+    //estimated from the turning between neighbouring chords of the length table
+    fn max_curvature(&self, table: &[(T, T)]) -> T {
+        let points: Vec<_> = table.iter().map(|(t, _)| self.point_at(*t)).collect();
+        points
+            .windows(3)
+            .map(|w| {
+                let (ax, ay) = ((w[1][0] - w[0][0]).value, (w[1][1] - w[0][1]).value);
+                let (bx, by) = ((w[2][0] - w[1][0]).value, (w[2][1] - w[1][1]).value);
+                let turning = (ax * by - ay * bx).atan2(ax * bx + ay * by).abs();
+                let chords = ax.hypot(ay) + bx.hypot(by);
+                if chords.is_zero() {
+                    T::zero()
+                } else {
+                    (turning + turning) / chords
+                }
+            })
+            .fold(T::zero(), T::max)
+    }
+    //pairs of parameter and accumulated chord length
+    fn length_table(&self, n: usize) -> Vec<(T, T)> {
+        let (t0, t1) = self.range;
+        let mut table = Vec::with_capacity(n + 1);
+        let mut prev = self.point_at(t0);
+        let mut len = T::zero();
+        table.push((t0, len));
+        for i in 1..=n {
+            let t = t0 + (t1 - t0) * T::from_usize(i).unwrap() / T::from_usize(n).unwrap();
+            let p = self.point_at(t);
+            len += (p[0].value - prev[0].value).hypot(p[1].value - prev[1].value);
+            table.push((t, len));
+            prev = p;
+        }
+        table
+    }
+}
+
+impl<F, D, L, T> IntoIterator for ParametricCurve<F, D, L, T>
+where
+    F: Fn(T) -> LenCo<L, T>,
+    D: Fn(T) -> LenCo<L, T>,
+    L: LengthType,
+    T: Num + Float + FromPrimitive,
+{
+    type IntoIter = std::vec::IntoIter<LenCo<L, T>>;
+    type Item = LenCo<L, T>;
+    //points are placed at equal arc length
+    fn into_iter(self) -> Self::IntoIter {
+        let mut table = self.length_table(TABLE_SIZE);
+        let section_num = |table: &Vec<(T, T)>| {
+            let total = table.last().unwrap().1;
+            match self.resolution {
+                Resolution::MinNumber(n) => {
+                    debug_assert!(n > 1);
+                    n - 1
+                }
+                Resolution::MinDistance(d) => (total / d.value).abs().to_usize().unwrap(),
+                Resolution::MaxDeviation(e) => {
+                    let curvature = self.max_curvature(table);
+                    deviation_sections(total * curvature, T::one() / curvature, e.value)
+                }
+            }
+            .max(1)
+        };
+        let mut n = section_num(&table);
+        if n * 4 > TABLE_SIZE {
+            table = self.length_table(n * 4);
+            n = section_num(&table);
+        }
+        let total = table.last().unwrap().1;
+        let mut j = 0;
+        let mut points = Vec::with_capacity(n + 1);
+        points.push(self.point_at(self.range.0));
+        for k in 1..n {
+            let s = total * T::from_usize(k).unwrap() / T::from_usize(n).unwrap();
+            while j + 2 < table.len() && table[j + 1].1 < s {
+                j += 1;
+            }
+            let ((ta, sa), (tb, sb)) = (table[j], table[j + 1]);
+            let t = if sb > sa {
+                ta + (tb - ta) * (s - sa) / (sb - sa)
+            } else {
+                ta
+            };
+            points.push(self.point_at(t));
+        }
+        points.push(self.point_at(self.range.1));
+        points.into_iter()
+    }
+}
+
+impl<F, D, L, T> Bias<Length<L, T>> for ParametricCurve<F, D, L, T>
+where
+    L: LengthType,
+    T: Num,
+{
+    fn bias(&mut self, b: Length<L, T>) {
+        self.bias += b;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cursor::{Cursor, Dir, Pos},
+        draw::curve::Curve,
+        zero, MICROMETER, NANOMETER,
+    };
+    use float_cmp::ApproxEq;
+
+    const MARGIN: (f64, i64) = (1e-9, 1);
+
+    fn quarter_circle(t: f64) -> LenCo<Absolute, f64> {
+        Coordinate::from([MICROMETER * 10. * t.cos(), MICROMETER * 10. * t.sin()])
+    }
+
+    #[test]
+    fn circle_curve() {
+        let mut curve = ParametricCurve::new(
+            quarter_circle,
+            (0., std::f64::consts::FRAC_PI_2),
+            Resolution::MinDistance(NANOMETER * 100.),
+        );
+        assert!(curve.start_ang().approx_eq(Angle::from_deg(90.), (1e-7, 1)));
+        assert!(curve.end_ang().sin().approx_eq(0., (1e-7, 1)));
+        assert!(curve.end_ang().cos().approx_eq(-1., (1e-7, 1)));
+        let points: Vec<_> = curve.into_iter().collect();
+        assert_eq!(points.len(), 158);
+        assert!(points[0].approx_eq(curve.start_pos(), MARGIN));
+        assert!(points.last().unwrap().approx_eq(curve.end_pos(), MARGIN));
+        let steps: Vec<_> = points
+            .windows(2)
+            .map(|w| (w[1][0] - w[0][0]).value.hypot((w[1][1] - w[0][1]).value))
+            .collect();
+        assert!(steps.iter().all(|s| (s - steps[0]).abs() < 1e-6));
+
+        //the center is on the left, so a positive bias shrinks the radius
+        curve.bias(MICROMETER);
+        assert!(curve
+            .start_pos()
+            .approx_eq([MICROMETER * 9., zero()].into(), (1e-7, 1)));
+        let analytic = ParametricCurve::with_derivative(
+            quarter_circle,
+            |t: f64| Coordinate::from([-MICROMETER * 10. * t.sin(), MICROMETER * 10. * t.cos()]),
+            (0., std::f64::consts::FRAC_PI_2),
+            Resolution::MinNumber(50),
+        );
+        assert_eq!(analytic.into_iter().count(), 50);
+        let coarse = ParametricCurve::new(
+            quarter_circle,
+            (0., std::f64::consts::FRAC_PI_2),
+            Resolution::MaxDeviation(NANOMETER),
+        );
+        assert_eq!(coarse.into_iter().count(), 57);
+        assert!(analytic.end_ang().sin().approx_eq(0., MARGIN));
+    }
+
+    #[test]
+    fn assemble_curve() {
+        let mut c: Cursor = Cursor::default();
+        let curve = ParametricCurve::new(
+            |t: f64| Coordinate::from([MICROMETER * t, MICROMETER * t * t]),
+            (0., 2.),
+            Resolution::MinNumber(20),
+        );
+        let _ = c.assemble(Curve::new(curve));
+        assert!(c
+            .pos
+            .approx_eq([MICROMETER * 2., MICROMETER * 4.].into(), MARGIN));
+        assert!(c.dir.approx_eq(Angle::from_rad(4f64.atan()), (1e-7, 1)));
+    }
+}
@@ -0,0 +1,246 @@
+use std::marker::PhantomData;
+
+use num::{traits::FloatConst, Float, FromPrimitive, Zero};
+
+use crate::{
+    units::{Absolute, Angle, Length, LengthType},
+    Num,
+};
+
+use super::{
+    coordinate::{Coordinate, LenCo},
+    curve::{Bias, LeftBias},
+    deviation_sections, Resolution,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SBendShape {
+    //`offset * (1 - cos(pi * x / length)) / 2`
+    Cosine,
+    //`offset * (x / length - sin(2 * pi * x / length) / (2 * pi))`, curvature is zero at both ends
+    Sine,
+    //cubic bezier with control points at the middle of the length
+    Bezier,
+}
+
+//shifts sideways by `offset` over `length` along x, the direction at both ends is unchanged
+#[derive(Debug, Clone, Copy)]
+pub struct SBend<L = Absolute, T = f64>
+where
+    L: LengthType,
+    T: Num,
+{
+    pub(crate) shape: SBendShape,
+    pub(crate) length: Length<L, T>,
+    pub(crate) offset: Length<L, T>,
+    pub(crate) bias: Length<L, T>,
+    pub(crate) resolution: Resolution<Length<L, T>>,
+}
+
+impl<L, T> SBend<L, T>
+where
+    L: LengthType,
+    T: Num + Float + FloatConst + FromPrimitive,
+{
+    pub fn new(
+        shape: SBendShape,
+        length: Length<L, T>,
+        offset: Length<L, T>,
+        resolution: Resolution<Length<L, T>>,
+    ) -> Self {
+        Self {
+            shape,
+            length,
+            offset,
+            bias: Zero::zero(),
+            resolution,
+        }
+    }
+    pub fn shape(&self) -> SBendShape {
+        self.shape
+    }
+    pub fn offset(&self) -> Length<L, T> {
+        self.offset
+    }
+    //length of the curve itself, not the projection on x
+    pub fn length(&self) -> Length<L, T> {
+        let n = 256;
+        let h = T::one() / T::from_usize(n).unwrap();
+        let speed = |t: T| {
+            let (dx, dy) = self.derivative(t);
+            dx.hypot(dy)
+        };
+        let mut sum = speed(T::zero()) + speed(T::one());
+        for i in 1..n {
+            let w = if i % 2 == 1 { 4 } else { 2 };
+            sum += T::from_u8(w).unwrap() * speed(h * T::from_usize(i).unwrap());
+        }
+        Length {
+            value: sum * h / T::from_u8(3).unwrap(),
+            marker: PhantomData,
+        }
+    }
+    fn raw_point(&self, t: T) -> (T, T) {
+        let (l, o) = (self.length.value, self.offset.value);
+        let (one, two, three) = (T::one(), T::one() + T::one(), T::from_u8(3).unwrap());
+        match self.shape {
+            SBendShape::Cosine => (l * t, o / two * (one - (T::PI() * t).cos())),
+            SBendShape::Sine => (l * t, o * (t - (two * T::PI() * t).sin() / (two * T::PI()))),
+            SBendShape::Bezier => (
+                three / two * l * t * (one - t) + l * t * t * t,
+                o * t * t * (three - two * t),
+            ),
+        }
+    }
+    fn derivative(&self, t: T) -> (T, T) {
+        let (l, o) = (self.length.value, self.offset.value);
+        let (one, two, three) = (T::one(), T::one() + T::one(), T::from_u8(3).unwrap());
+        match self.shape {
+            SBendShape::Cosine => (l, o * T::PI() / two * (T::PI() * t).sin()),
+            SBendShape::Sine => (l, o * (one - (two * T::PI() * t).cos())),
+            SBendShape::Bezier => (
+                three / two * l * (one - two * t) + three * l * t * t,
+                two * three * o * t * (one - t),
+            ),
+        }
+    }
+    fn second_derivative(&self, t: T) -> (T, T) {
+        let (l, o) = (self.length.value, self.offset.value);
+        let (one, two, three) = (T::one(), T::one() + T::one(), T::from_u8(3).unwrap());
+        match self.shape {
+            SBendShape::Cosine => (T::zero(), o * T::PI() * T::PI() / two * (T::PI() * t).cos()),
+            SBendShape::Sine => (T::zero(), o * two * T::PI() * (two * T::PI() * t).sin()),
+            SBendShape::Bezier => (
+                two * three * l * t - three * l,
+                two * three * o * (one - two * t),
+            ),
+        }
+    }
+    //points are evenly spaced in `t`, so the turning per unit `t` and the smallest radius are taken separately
+    fn deviation_sections(&self, deviation: T) -> usize {
+        let n = 256;
+        let (mut turning, mut curvature) = (T::zero(), T::zero());
+        for i in 0..=n {
+            let t = T::from_usize(i).unwrap() / T::from_usize(n).unwrap();
+            let (d1, d2) = (self.derivative(t), self.second_derivative(t));
+            let cross = (d1.0 * d2.1 - d1.1 * d2.0).abs();
+            let speed = d1.0.hypot(d1.1);
+            turning = turning.max(cross / (speed * speed));
+            curvature = curvature.max(cross / (speed * speed * speed));
+        }
+        let radius = (T::one() / curvature - self.bias.value.abs()).abs();
+        deviation_sections(turning, radius, deviation)
+    }
+    pub(crate) fn point_at(&self, t: T) -> LenCo<L, T> {
+        let (x, y) = self.raw_point(t);
+        let (dx, dy) = self.derivative(t);
+        let k = self.bias.value / dx.hypot(dy);
+        let to_len = |v: T| Length {
+            value: v,
+            marker: PhantomData,
+        };
+        Coordinate::from([to_len(x - dy * k), to_len(y + dx * k)])
+    }
+    pub(crate) fn ang_at(&self, t: T) -> Angle<T> {
+        let (dx, dy) = self.derivative(t);
+        Angle::from_rad(dy.atan2(dx))
+    }
+}
+
+impl<L, T> IntoIterator for SBend<L, T>
+where
+    L: LengthType,
+    T: Num + Float + FloatConst + FromPrimitive,
+{
+    type IntoIter = impl DoubleEndedIterator<Item = LenCo<L, T>>;
+    type Item = LenCo<L, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        let section_num = match self.resolution {
+            Resolution::MinNumber(n) => {
+                debug_assert!(n > 1);
+                n - 1
+            }
+            Resolution::MinDistance(d) => (self.length() / d).abs().to_usize().unwrap(),
+            Resolution::MaxDeviation(e) => self.deviation_sections(e.value),
+        }
+        .max(1);
+        (0..=section_num).map(move |s| {
+            self.point_at(T::from_usize(s).unwrap() / T::from_usize(section_num).unwrap())
+        })
+    }
+}
+
+impl<L, T> Bias<Length<L, T>> for SBend<L, T>
+where
+    L: LengthType,
+    T: Num,
+{
+    fn bias(&mut self, b: Length<L, T>) {
+        self.bias += b;
+    }
+}
+
+impl<L, T> LeftBias<Length<L, T>> for SBend<L, T>
+where
+    L: LengthType,
+    T: Num,
+{
+    fn left_bias(&self, b: Length<L, T>) -> Length<L, T> {
+        b
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cursor::{Dir, Pos},
+        zero, MICROMETER, NANOMETER,
+    };
+    use float_cmp::ApproxEq;
+
+    const MARGIN: (f64, i64) = (1e-9, 1);
+
+    #[test]
+    fn sbend_ends() {
+        for shape in [SBendShape::Cosine, SBendShape::Sine, SBendShape::Bezier] {
+            let bend = SBend::new(
+                shape,
+                MICROMETER * 40.,
+                MICROMETER * 5.,
+                Resolution::MinDistance(NANOMETER * 20.),
+            );
+            assert!(bend.start_pos().approx_eq([zero(), zero()].into(), MARGIN));
+            assert!(bend
+                .end_pos()
+                .approx_eq([MICROMETER * 40., MICROMETER * 5.].into(), MARGIN));
+            assert!(bend.start_ang().approx_eq(Angle::from_rad(0.), MARGIN));
+            assert!(bend.end_ang().approx_eq(Angle::from_rad(0.), MARGIN));
+            assert!(bend.length() > MICROMETER * 40.);
+            let points: Vec<_> = bend.into_iter().collect();
+            assert!(points.last().unwrap().approx_eq(bend.end_pos(), MARGIN));
+            let coarse = SBend {
+                resolution: Resolution::MaxDeviation(NANOMETER),
+                ..bend
+            };
+            assert!(coarse.into_iter().count() < points.len());
+        }
+    }
+
+    #[test]
+    fn bias_sbend() {
+        let mut bend = SBend::new(
+            SBendShape::Sine,
+            MICROMETER * 40.,
+            -MICROMETER * 5.,
+            Resolution::MinNumber(100),
+        );
+        bend.bias(MICROMETER);
+        assert!(bend
+            .start_pos()
+            .approx_eq([zero(), MICROMETER].into(), MARGIN));
+        assert!(bend
+            .end_pos()
+            .approx_eq([MICROMETER * 40., -MICROMETER * 4.].into(), MARGIN));
+    }
+}
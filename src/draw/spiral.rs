@@ -0,0 +1,532 @@
+use std::marker::PhantomData;
+
+use num::{traits::FloatConst, Float, FromPrimitive, Zero};
+
+use crate::{
+    units::{Absolute, Angle, Length, LengthType},
+    Num,
+};
+
+use super::{
+    coordinate::{Coordinate, LenCo},
+    curve::Bias,
+    Resolution, MAX_SECTIONS,
+};
+
+//`r = radius + pitch * theta / (2 * pi)` around the origin, traversed from `angle.0` to `angle.1`, several turns are
+//given as angles beyond one turn, a negative pitch shrinks the radius as the angle grows
+#[derive(Debug, Clone, Copy)]
+pub struct ArchimedeanSpiral<L = Absolute, T = f64>
+where
+    L: LengthType,
+    T: Num,
+{
+    pub(crate) radius: Length<L, T>,
+    pub(crate) pitch: Length<L, T>,
+    pub(crate) angle: (Angle<T>, Angle<T>),
+    pub(crate) bias: Length<L, T>,
+    pub(crate) resolution: Resolution<Length<L, T>>,
+}
+
+impl<L, T> ArchimedeanSpiral<L, T>
+where
+    L: LengthType,
+    T: Num + Float + FloatConst + FromPrimitive,
+{
+    pub fn new(
+        radius: Length<L, T>,
+        pitch: Length<L, T>,
+        angle: (Angle<T>, Angle<T>),
+        resolution: Resolution<Length<L, T>>,
+    ) -> Self {
+        Self {
+            radius,
+            pitch,
+            angle,
+            bias: Zero::zero(),
+            resolution,
+        }
+    }
+    pub fn pitch(&self) -> Length<L, T> {
+        self.pitch
+    }
+    pub fn radius_at(&self, angle: Angle<T>) -> Length<L, T> {
+        to_len(self.raw_radius(angle.to_rad()))
+    }
+    //length of the unbiased curve
+    pub fn length(&self) -> Length<L, T> {
+        let (a, b) = (self.angle.0.to_rad(), self.angle.1.to_rad());
+        to_len(simpson((a, b), |t| self.speed(t, T::zero())))
+    }
+    fn raw_radius(&self, theta: T) -> T {
+        self.radius.value + self.pitch.value * theta / T::TAU()
+    }
+    fn sense(&self) -> T {
+        if self.angle.1.to_rad() < self.angle.0.to_rad() {
+            -T::one()
+        } else {
+            T::one()
+        }
+    }
+    //in the direction of travel
+    fn derivative(&self, theta: T) -> (T, T) {
+        let (r, b) = (self.raw_radius(theta), self.pitch.value / T::TAU());
+        let (s, c) = theta.sin_cos();
+        let k = self.sense();
+        (k * (b * c - r * s), k * (b * s + r * c))
+    }
+    //signed radius of curvature of the unbiased curve, positive when turning left
+    fn curvature_radius(&self, theta: T) -> T {
+        let (r, b) = (self.raw_radius(theta), self.pitch.value / T::TAU());
+        let two = T::one() + T::one();
+        (r * r + b * b).powf(T::from_f64(1.5).unwrap()) / (r * r + two * b * b) * self.sense()
+    }
+    //length per radian of the curve moved by `bias`
+    fn speed(&self, theta: T, bias: T) -> T {
+        let (dx, dy) = self.derivative(theta);
+        let rho = self.curvature_radius(theta);
+        dx.hypot(dy) * ((rho - bias) / rho).abs()
+    }
+    fn params(&self, step: impl Fn(T) -> T) -> Vec<T> {
+        let b = self.bias.value;
+        spacing(
+            (self.angle.0.to_rad(), self.angle.1.to_rad()),
+            |t| self.speed(t, b),
+            |t| self.curvature_radius(t) - b,
+            step,
+        )
+    }
+    pub(crate) fn point_at(&self, theta: T) -> LenCo<L, T> {
+        let r = self.raw_radius(theta);
+        let (s, c) = theta.sin_cos();
+        let (dx, dy) = self.derivative(theta);
+        let k = self.bias.value / dx.hypot(dy);
+        Coordinate::from([to_len(r * c - dy * k), to_len(r * s + dx * k)])
+    }
+    pub(crate) fn ang_at(&self, theta: T) -> Angle<T> {
+        let (dx, dy) = self.derivative(theta);
+        Angle::from_rad(dy.atan2(dx))
+    }
+}
+
+impl<L, T> IntoIterator for ArchimedeanSpiral<L, T>
+where
+    L: LengthType,
+    T: Num + Float + FloatConst + FromPrimitive,
+{
+    type IntoIter = impl DoubleEndedIterator<Item = LenCo<L, T>>;
+    type Item = LenCo<L, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.params(step(self.resolution, self.length().value))
+            .into_iter()
+            .map(move |t| self.point_at(t))
+    }
+}
+
+impl<L, T> Bias<Length<L, T>> for ArchimedeanSpiral<L, T>
+where
+    L: LengthType,
+    T: Num,
+{
+    fn bias(&mut self, b: Length<L, T>) {
+        self.bias += b;
+    }
+}
+
+//two interleaved archimedean arms with `pitch` between neighbouring lanes, the curve winds inward along one arm for
+//`turns` turns, crosses the center through an s-bend of two arcs meeting at the origin and winds outward along the
+//other arm, so both ends are on the outside, point symmetric to each other and head in the same direction
+#[derive(Debug, Clone, Copy)]
+pub struct DoubleSpiral<L = Absolute, T = f64>
+where
+    L: LengthType,
+    T: Num,
+{
+    pub(crate) radius: Length<L, T>,
+    pub(crate) pitch: Length<L, T>,
+    pub(crate) turns: T,
+    pub(crate) bias: Length<L, T>,
+    pub(crate) resolution: Resolution<Length<L, T>>,
+}
+
+//arc around `center` starting at `start`, turning by `angle` to the left if positive
+#[derive(Debug, Clone, Copy)]
+struct CenterArc<T> {
+    center: (T, T),
+    start: (T, T),
+    angle: T,
+}
+
+impl<T: Float> CenterArc<T> {
+    fn radius(&self) -> T {
+        (self.start.0 - self.center.0).hypot(self.start.1 - self.center.1)
+    }
+    //signed radius of curvature of the arc moved by `bias`, positive when turning left
+    fn biased_radius(&self, bias: T) -> T {
+        self.radius() * self.angle.signum() - bias
+    }
+    fn point_at(&self, phi: T, bias: T) -> (T, T) {
+        let k = self.biased_radius(bias) / (self.radius() * self.angle.signum());
+        let (s, c) = (phi * self.angle.signum()).sin_cos();
+        let (x, y) = (self.start.0 - self.center.0, self.start.1 - self.center.1);
+        (
+            self.center.0 + (x * c - y * s) * k,
+            self.center.1 + (x * s + y * c) * k,
+        )
+    }
+}
+
+impl<L, T> DoubleSpiral<L, T>
+where
+    L: LengthType,
+    T: Num + Float + FloatConst + FromPrimitive,
+{
+    //`radius` is where the arms meet the s-bend, `turns` is counted for each arm
+    pub fn new(
+        radius: Length<L, T>,
+        pitch: Length<L, T>,
+        turns: T,
+        resolution: Resolution<Length<L, T>>,
+    ) -> Self {
+        Self {
+            radius,
+            pitch,
+            turns,
+            bias: Zero::zero(),
+            resolution,
+        }
+    }
+    pub fn pitch(&self) -> Length<L, T> {
+        self.pitch
+    }
+    pub fn turns(&self) -> T {
+        self.turns
+    }
+    //the outward arm with the opposite bias, the inward one is it mirrored through the origin and reversed
+    fn arm(&self) -> ArchimedeanSpiral<L, T> {
+        ArchimedeanSpiral {
+            radius: self.radius,
+            pitch: self.pitch * (T::one() + T::one()),
+            angle: (
+                Angle::from_rad(T::zero()),
+                Angle::from_rad(T::TAU() * self.turns),
+            ),
+            bias: -self.bias,
+            resolution: self.resolution,
+        }
+    }
+    //the first arc of the s-bend, from the end of the inward arm to the origin, the second one is its point mirror
+    fn center(&self) -> CenterArc<T> {
+        let arm = self.arm();
+        let p = (-arm.raw_radius(T::zero()), T::zero());
+        let (dx, dy) = arm.derivative(T::zero());
+        let norm = dx.hypot(dy);
+        let left = (-dy / norm, dx / norm);
+        let dot = p.0 * left.0 + p.1 * left.1;
+        let radius = (p.0 * p.0 + p.1 * p.1) / ((T::one() + T::one()) * dot.abs());
+        let sense = -dot.signum();
+        let center = (p.0 + left.0 * radius * sense, p.1 + left.1 * radius * sense);
+        let from = (p.1 - center.1).atan2(p.0 - center.0);
+        let to = (-center.1).atan2(-center.0);
+        let mut angle = (to - from) * sense;
+        while angle < T::zero() {
+            angle += T::TAU();
+        }
+        CenterArc {
+            center,
+            start: p,
+            angle: angle * sense,
+        }
+    }
+    //length of the unbiased curve
+    pub fn length(&self) -> Length<L, T> {
+        let two = T::one() + T::one();
+        let arc = self.center();
+        self.arm().length() * two + to_len(arc.radius() * arc.angle.abs() * two)
+    }
+    fn pieces(&self) -> (ArchimedeanSpiral<L, T>, CenterArc<T>, CenterArc<T>) {
+        let first = self.center();
+        let second = CenterArc {
+            center: (-first.center.0, -first.center.1),
+            start: (T::zero(), T::zero()),
+            angle: -first.angle,
+        };
+        (self.arm(), first, second)
+    }
+    pub(crate) fn start(&self) -> (LenCo<L, T>, Angle<T>) {
+        let arm = self.arm();
+        let end = arm.angle.1.to_rad();
+        (mirrored(arm.point_at(end)), arm.ang_at(end))
+    }
+    pub(crate) fn end(&self) -> (LenCo<L, T>, Angle<T>) {
+        let arm = ArchimedeanSpiral {
+            bias: self.bias,
+            ..self.arm()
+        };
+        let end = arm.angle.1.to_rad();
+        (arm.point_at(end), arm.ang_at(end))
+    }
+}
+
+impl<L, T> IntoIterator for DoubleSpiral<L, T>
+where
+    L: LengthType,
+    T: Num + Float + FloatConst + FromPrimitive,
+{
+    type IntoIter = std::vec::IntoIter<LenCo<L, T>>;
+    type Item = LenCo<L, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        let (arm, first, second) = self.pieces();
+        let b = self.bias.value;
+        let step = step(self.resolution, self.length().value);
+        //the inward arm is the outward one mirrored through the origin with the opposite bias and reversed
+        let mut points: Vec<_> = arm
+            .params(&step)
+            .into_iter()
+            .rev()
+            .map(|t| mirrored(arm.point_at(t)))
+            .collect();
+        for arc in [first, second] {
+            let rho = arc.biased_radius(b);
+            let params = spacing((T::zero(), arc.angle.abs()), |_| rho.abs(), |_| rho, &step);
+            points.extend(params.into_iter().skip(1).map(|phi| {
+                let (x, y) = arc.point_at(phi, b);
+                Coordinate::from([to_len(x), to_len(y)])
+            }));
+        }
+        let outward = ArchimedeanSpiral {
+            bias: self.bias,
+            ..arm
+        };
+        points.extend(
+            outward
+                .params(&step)
+                .into_iter()
+                .skip(1)
+                .map(|t| outward.point_at(t)),
+        );
+        points.into_iter()
+    }
+}
+
+impl<L, T> Bias<Length<L, T>> for DoubleSpiral<L, T>
+where
+    L: LengthType,
+    T: Num,
+{
+    fn bias(&mut self, b: Length<L, T>) {
+        self.bias += b;
+    }
+}
+
+fn to_len<L: LengthType, T: Num>(value: T) -> Length<L, T> {
+    Length {
+        value,
+        marker: PhantomData,
+    }
+}
+
+//point mirror through the origin
+fn mirrored<L: LengthType, T: Num>(c: LenCo<L, T>) -> LenCo<L, T> {
+    Coordinate::from([-c[0], -c[1]])
+}
+
+//Simpson's rule with 1024 double steps
+fn simpson<T: Float + FromPrimitive>((a, b): (T, T), f: impl Fn(T) -> T) -> T {
+    let n = 2048;
+    let h = (b - a) / T::from_usize(n).unwrap();
+    let mut sum = f(a) + f(b);
+    for i in 1..n {
+        let w = if i % 2 == 1 { 4 } else { 2 };
+        sum = sum + T::from_u8(w).unwrap() * f(a + h * T::from_usize(i).unwrap());
+    }
+    (sum * h / T::from_u8(3).unwrap()).abs()
+}
+
+//longest section allowed at a signed radius of curvature, a minimum number of points is spread over `total`
+fn step<L: LengthType, T: Float + FromPrimitive>(
+    resolution: Resolution<Length<L, T>>,
+    total: T,
+) -> impl Fn(T) -> T {
+    move |radius: T| match resolution {
+        Resolution::MinNumber(n) => {
+            debug_assert!(n > 1);
+            total / T::from_usize(n - 1).unwrap()
+        }
+        Resolution::MinDistance(d) => d.value.abs(),
+        Resolution::MaxDeviation(e) => {
+            assert!(
+                e.value > T::zero(),
+                "the maximum deviation has to be positive"
+            );
+            let radius = radius.abs();
+            let ratio = (T::one() - e.value / radius).max(-T::one());
+            (T::one() + T::one()) * radius * ratio.acos()
+        }
+    }
+}
+
+//parameters from `range.0` to `range.1` spaced evenly in sections, `speed` is the length per unit parameter and
+//`radius` the radius of curvature where it is taken
+fn spacing<T: Float + FromPrimitive>(
+    range: (T, T),
+    speed: impl Fn(T) -> T,
+    radius: impl Fn(T) -> T,
+    step: impl Fn(T) -> T,
+) -> Vec<T> {
+    let n = 1024;
+    let h = (range.1 - range.0) / T::from_usize(n).unwrap();
+    let at = |i: usize| range.0 + h * T::from_usize(i).unwrap();
+    let density = |t: T| speed(t) * h.abs() / step(radius(t));
+    let mut table = Vec::with_capacity(n + 1);
+    table.push(T::zero());
+    for i in 0..n {
+        let last = table[i];
+        table.push(last + (density(at(i)) + density(at(i + 1))) / (T::one() + T::one()));
+    }
+    let total = table[n];
+    let sections = total
+        .ceil()
+        .to_usize()
+        .unwrap_or(MAX_SECTIONS)
+        .clamp(1, MAX_SECTIONS);
+    let mut params = Vec::with_capacity(sections + 1);
+    let mut i = 0;
+    for k in 0..sections {
+        let target = total * T::from_usize(k).unwrap() / T::from_usize(sections).unwrap();
+        while i + 1 < n && table[i + 1] < target {
+            i += 1;
+        }
+        let width = table[i + 1] - table[i];
+        let frac = if width > T::zero() {
+            (target - table[i]) / width
+        } else {
+            T::zero()
+        };
+        params.push(at(i) + h * frac);
+    }
+    params.push(range.1);
+    params
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cursor::{Dir, Pos},
+        draw::curve::Sweep,
+        zero, MICROMETER, NANOMETER,
+    };
+    use float_cmp::ApproxEq;
+
+    const MARGIN: (f64, i64) = (1e-9, 1);
+
+    fn gaps(points: &[LenCo<Absolute, f64>]) -> impl Iterator<Item = f64> + '_ {
+        points
+            .windows(2)
+            .map(|w| (w[1][0] - w[0][0]).value.hypot((w[1][1] - w[0][1]).value))
+    }
+
+    #[test]
+    fn archimedean_spiral() {
+        let spiral = ArchimedeanSpiral::new(
+            MICROMETER * 10.,
+            MICROMETER * 5.,
+            (
+                Angle::from_rad(0.),
+                Angle::from_rad(4. * std::f64::consts::PI),
+            ),
+            Resolution::MinDistance(NANOMETER * 20.),
+        );
+        assert!(spiral
+            .start_pos()
+            .approx_eq([MICROMETER * 10., zero()].into(), MARGIN));
+        assert!(spiral
+            .end_pos()
+            .approx_eq([MICROMETER * 20., zero()].into(), MARGIN));
+        let b = 5. / std::f64::consts::TAU;
+        assert!(spiral
+            .start_ang()
+            .approx_eq(Angle::from_rad(10f64.atan2(b)), MARGIN));
+        //closed form of the integral of `sqrt(r^2 + b^2) / b` over `r`
+        let primitive = |r: f64| {
+            let h = r.hypot(b);
+            (r * h + b * b * (r + h).ln()) / (2. * b)
+        };
+        let exact = primitive(20.) - primitive(10.);
+        assert!((spiral.length().value - exact).abs() < exact * 1e-9);
+        let points: Vec<_> = spiral.into_iter().collect();
+        assert!(points.last().unwrap().approx_eq(spiral.end_pos(), MARGIN));
+        assert!(gaps(&points).all(|g| g < 0.02 * 1.001));
+        let coarse = ArchimedeanSpiral {
+            resolution: Resolution::MaxDeviation(NANOMETER),
+            ..spiral
+        };
+        assert!(coarse.into_iter().count() < points.len());
+
+        let mut inward = ArchimedeanSpiral {
+            angle: (spiral.angle.1, spiral.angle.0),
+            ..spiral
+        };
+        inward.bias(MICROMETER);
+        //the left side of a clockwise spiral is outside
+        let n = 20f64.hypot(b);
+        assert!(inward.start_pos().approx_eq(
+            [MICROMETER * (20. + 20. / n), -MICROMETER * (b / n)].into(),
+            MARGIN
+        ));
+        let n = 10f64.hypot(b);
+        assert!(inward.end_pos().approx_eq(
+            [MICROMETER * (10. + 10. / n), -MICROMETER * (b / n)].into(),
+            MARGIN
+        ));
+    }
+
+    #[test]
+    fn double_spiral() {
+        let spiral = DoubleSpiral::new(
+            MICROMETER * 20.,
+            MICROMETER * 3.,
+            2.,
+            Resolution::MinDistance(NANOMETER * 50.),
+        );
+        assert!(spiral
+            .start_pos()
+            .approx_eq([-MICROMETER * 32., zero()].into(), MARGIN));
+        assert!(spiral
+            .end_pos()
+            .approx_eq([MICROMETER * 32., zero()].into(), MARGIN));
+        assert!(spiral.start_ang().approx_eq(spiral.end_ang(), MARGIN));
+        let points: Vec<_> = spiral.into_iter().collect();
+        assert!(points[0].approx_eq(spiral.start_pos(), MARGIN));
+        assert!(points.last().unwrap().approx_eq(spiral.end_pos(), MARGIN));
+        assert!(points
+            .iter()
+            .any(|p| p.approx_eq([zero(), zero()].into(), MARGIN)));
+        assert!(gaps(&points).all(|g| g < 0.05 * 1.001));
+        let chords: f64 = gaps(&points).sum();
+        assert!((chords - spiral.length().value).abs() < spiral.length().value * 1e-6);
+
+        //the biased edges stay continuous across the s-bend
+        let width = MICROMETER * 1.;
+        let outline: Vec<_> = spiral
+            .sweep((-width / 2., width / 2.))
+            .area
+            .into_iter()
+            .collect();
+        let edge = |b| {
+            let mut edge = spiral;
+            edge.bias(b);
+            edge.into_iter().collect::<Vec<_>>()
+        };
+        let (right, left) = (edge(-width / 2.), edge(width / 2.));
+        assert!(outline.len() >= right.len() + left.len());
+        assert!(outline[right.len() - 1].approx_eq(*right.last().unwrap(), MARGIN));
+        assert!(gaps(&right).chain(gaps(&left)).all(|g| g < 0.05 * 1.1));
+        let across = |a: LenCo<Absolute, f64>, b: LenCo<Absolute, f64>| {
+            (a[0] - b[0]).value.hypot((a[1] - b[1]).value)
+        };
+        assert!((across(right[0], left[0]) - 1.).abs() < 1e-9);
+        assert!((across(right[right.len() - 1], left[left.len() - 1]) - 1.).abs() < 1e-9);
+    }
+}
@@ -1,6 +1,6 @@
 use std::{iter::Map, ops::Mul};
 
-use nalgebra::{ClosedAdd, RealField, Rotation2, Similarity, Translation};
+use nalgebra::{Matrix2, Point2, RealField, Rotation2, Vector2};
 
 use crate::{
     units::{Angle, Length, LengthType},
@@ -8,8 +8,7 @@ use crate::{
 };
 
 use super::{
-    coordinate::Coordinate,
-    coordinate::MulAsScalar,
+    coordinate::{Coordinate, LenCo},
     curve::{
         groups::{Compound, Group},
         Area, Curve,
@@ -49,6 +48,127 @@ where
     }
 }
 
+//affine map of the plane, the matrix then the offset, e.g. a rotation with a mirror and a magnification followed by
+//a translation, composed transforms collapse into one of these
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform<T: Num = f64> {
+    pub(crate) matrix: Matrix2<T>,
+    pub(crate) offset: Vector2<T>,
+}
+
+//geometry moved by one `Transform`, however many were composed into it
+pub type Transformed<E, L, T> = <E as Transfer<Length<L, T>>>::Output<Transform<T>>;
+
+impl<T: Num + RealField> Default for Transform<T> {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl<T: Num + RealField> Transform<T> {
+    pub fn identity() -> Self {
+        Self::linear(Matrix2::identity())
+    }
+    pub fn translation<L: LengthType>(x: Length<L, T>, y: Length<L, T>) -> Self {
+        Self {
+            matrix: Matrix2::identity(),
+            offset: Vector2::new(x.value, y.value),
+        }
+    }
+    pub fn rotation(ang: Angle<T>) -> Self {
+        Self::linear(*Rotation2::new(ang.to_rad()).matrix())
+    }
+    pub fn scaling(mag: T) -> Self {
+        Self::linear(Matrix2::identity() * mag)
+    }
+    //about the x axis, y is negated
+    pub fn reflection_x() -> Self {
+        Self::linear(Matrix2::new(T::one(), T::zero(), T::zero(), -T::one()))
+    }
+    //about the y axis, x is negated
+    pub fn reflection_y() -> Self {
+        Self::linear(Matrix2::new(-T::one(), T::zero(), T::zero(), T::one()))
+    }
+    //about the line through `point` along `ang`
+    pub fn reflection<L: LengthType>(point: Coordinate<Length<L, T>>, ang: Angle<T>) -> Self {
+        let two = T::one() + T::one();
+        let (sin, cos) = ((ang.to_rad() * two).sin(), (ang.to_rad() * two).cos());
+        Self::translation(-point[0], -point[1])
+            .then(Self::linear(Matrix2::new(cos, sin, sin, -cos)))
+            .translate(point[0], point[1])
+    }
+    fn linear(matrix: Matrix2<T>) -> Self {
+        Self {
+            matrix,
+            offset: Vector2::zeros(),
+        }
+    }
+    //`next` applied after this one
+    pub fn then(self, next: Self) -> Self {
+        Self {
+            matrix: next.matrix * self.matrix,
+            offset: next.matrix * self.offset + next.offset,
+        }
+    }
+    pub fn translate<L: LengthType>(self, x: Length<L, T>, y: Length<L, T>) -> Self {
+        self.then(Self::translation(x, y))
+    }
+    pub fn rotate(self, ang: Angle<T>) -> Self {
+        self.then(Self::rotation(ang))
+    }
+    pub fn scale(self, mag: T) -> Self {
+        self.then(Self::scaling(mag))
+    }
+    pub fn mirror_x(self) -> Self {
+        self.then(Self::reflection_x())
+    }
+    pub fn mirror_y(self) -> Self {
+        self.then(Self::reflection_y())
+    }
+    pub fn mirror<L: LengthType>(self, point: Coordinate<Length<L, T>>, ang: Angle<T>) -> Self {
+        self.then(Self::reflection(point, ang))
+    }
+    pub fn inverse(self) -> Option<Self> {
+        let matrix = self.matrix.try_inverse()?;
+        Some(Self {
+            matrix,
+            offset: -(matrix * self.offset),
+        })
+    }
+    //the orientation is reversed
+    pub fn is_mirrored(&self) -> bool {
+        self.matrix.determinant() < T::zero()
+    }
+    pub fn apply<L: LengthType>(&self, p: LenCo<L, T>) -> LenCo<L, T> {
+        let p = p.to_basic().0.coords;
+        Coordinate::from_basic(Coordinate(Point2::from(self.matrix * p + self.offset)))
+    }
+    //direction of a path leaving along `dir` after the transform
+    pub fn apply_dir(&self, dir: Angle<T>) -> Angle<T> {
+        let v = self.matrix * Vector2::new(dir.to_rad().cos(), dir.to_rad().sin());
+        Angle::from_rad(v[1].atan2(v[0]))
+    }
+}
+
+impl<L: LengthType, T: Num + RealField> FnOnce<(LenCo<L, T>,)> for Transform<T> {
+    type Output = LenCo<L, T>;
+    extern "rust-call" fn call_once(self, args: (LenCo<L, T>,)) -> Self::Output {
+        self.apply(args.0)
+    }
+}
+
+impl<L: LengthType, T: Num + RealField> FnMut<(LenCo<L, T>,)> for Transform<T> {
+    extern "rust-call" fn call_mut(&mut self, args: (LenCo<L, T>,)) -> Self::Output {
+        self.apply(args.0)
+    }
+}
+
+impl<L: LengthType, T: Num + RealField> Fn<(LenCo<L, T>,)> for Transform<T> {
+    extern "rust-call" fn call(&self, args: (LenCo<L, T>,)) -> Self::Output {
+        self.apply(args.0)
+    }
+}
+
 pub trait Transfer<Q>: Sized
 where
     Q: Quantity,
@@ -68,32 +188,52 @@ where
     L: LengthType,
     T: Num,
 {
-    fn translate(
-        self,
-        x: Length<L, T>,
-        y: Length<L, T>,
-    ) -> Self::Output<MulOpClosure<MulAsScalar<Translation<T, 2>>>>
+    //every step is one `Transform`, compose them with `Transform::then` first to move the points only once
+    fn translate(self, x: Length<L, T>, y: Length<L, T>) -> Transformed<Self, L, T>
+    where
+        T: RealField,
+    {
+        self.transform(Transform::translation(x, y))
+    }
+    fn scale(self, scale: T) -> Transformed<Self, L, T>
     where
-        T: ClosedAdd + Copy,
+        T: RealField,
     {
-        self.matrix_trans(MulAsScalar(Translation::<T, 2>::from([x.value, y.value])))
+        self.transform(Transform::scaling(scale))
     }
-    fn scale(
-        self,
-        scale: T,
-    ) -> Self::Output<MulOpClosure<MulAsScalar<Similarity<T, Rotation2<T>, 2>>>>
+    fn rotate(self, ang: Angle<T>) -> Transformed<Self, L, T>
     where
-        T: RealField + Copy,
+        T: RealField,
     {
-        self.matrix_trans(MulAsScalar(Similarity::<T, Rotation2<T>, 2>::from_scaling(
-            scale,
-        )))
+        self.transform(Transform::rotation(ang))
     }
-    fn rotate(self, ang: Angle<T>) -> Self::Output<MulOpClosure<MulAsScalar<Rotation2<T>>>>
+    //reflection about the x axis, y is negated
+    fn mirror_x(self) -> Transformed<Self, L, T>
     where
-        T: RealField + Copy,
+        T: RealField,
     {
-        self.matrix_trans(MulAsScalar(Rotation2::new(ang.to_rad())))
+        self.transform(Transform::reflection_x())
+    }
+    //reflection about the y axis, x is negated
+    fn mirror_y(self) -> Transformed<Self, L, T>
+    where
+        T: RealField,
+    {
+        self.transform(Transform::reflection_y())
+    }
+    //reflection about the line through `point` along `ang`
+    fn mirror(self, point: Coordinate<Length<L, T>>, ang: Angle<T>) -> Transformed<Self, L, T>
+    where
+        T: RealField,
+    {
+        self.transform(Transform::reflection(point, ang))
+    }
+    //any number of transforms in one step
+    fn transform(self, t: Transform<T>) -> Transformed<Self, L, T>
+    where
+        T: RealField,
+    {
+        self.transfer(t)
     }
 }
 
@@ -189,4 +329,87 @@ mod tests {
                 APPROX_EQ_MARGIN
             ),);
     }
+
+    #[test]
+    fn mirror_coordinate() {
+        let coor = Coordinate::from((MILLIMETER, MILLIMETER * 2.));
+        let point = || std::iter::once(coor).into_curve();
+        assert_eq!(
+            point().mirror_x().into_iter().next().unwrap(),
+            Coordinate::from((MILLIMETER, MILLIMETER * -2.))
+        );
+        assert_eq!(
+            point().mirror_y().into_iter().next().unwrap(),
+            Coordinate::from((MILLIMETER * -1., MILLIMETER * 2.))
+        );
+        //about y = x + 1, the point on the line stays where it is
+        let line = (
+            Coordinate::from((MILLIMETER * 0., MILLIMETER)),
+            Angle::from_deg(45.),
+        );
+        assert!(point()
+            .mirror(line.0, line.1)
+            .into_iter()
+            .next()
+            .unwrap()
+            .approx_eq(coor, APPROX_EQ_MARGIN));
+        assert!(
+            std::iter::once(Coordinate::from((MILLIMETER * 2., MILLIMETER * 0.)))
+                .into_curve()
+                .mirror(line.0, line.1)
+                .into_iter()
+                .next()
+                .unwrap()
+                .approx_eq(
+                    Coordinate::from((MILLIMETER * -1., MILLIMETER * 3.)),
+                    APPROX_EQ_MARGIN
+                )
+        );
+    }
+
+    #[test]
+    fn compose_transform() {
+        use crate::units::Absolute;
+        let square = || Area {
+            area: vec![
+                Coordinate::from((MILLIMETER * 0., MILLIMETER * 0.)),
+                Coordinate::from((MILLIMETER, MILLIMETER * 0.)),
+                Coordinate::from((MILLIMETER, MILLIMETER)),
+            ],
+        };
+        let t = Transform::translation(MILLIMETER, MILLIMETER * 0.)
+            .rotate(Angle::from_deg(90.))
+            .mirror_y()
+            .scale(2.);
+        assert!(t.is_mirrored());
+        //the chained transfers and the single transform end up at the same points
+        let chained: Vec<_> = square()
+            .translate(MILLIMETER, MILLIMETER * 0.)
+            .rotate(Angle::from_deg(90.))
+            .mirror_y()
+            .scale(2.)
+            .into_iter()
+            .collect();
+        type Square = Area<Vec<LenCo<Absolute, f64>>>;
+        let placed: Vec<Transformed<Square, Absolute, f64>> = vec![square().transform(t)];
+        let composed: Vec<_> = placed.into_iter().flatten().collect();
+        assert_eq!(chained.len(), composed.len());
+        assert!(chained
+            .into_iter()
+            .zip(composed.iter())
+            .all(|(a, b)| a.approx_eq(*b, APPROX_EQ_MARGIN)));
+        assert!(composed[0].approx_eq(
+            Coordinate::from((MILLIMETER * 0., MILLIMETER * 2.)),
+            APPROX_EQ_MARGIN
+        ));
+        //x turns to y by the rotation, which the mirror about the y axis keeps
+        assert!(t
+            .apply_dir(Angle::from_deg(0.))
+            .approx_eq(Angle::from_deg(90.), APPROX_EQ_MARGIN));
+        let back = t.inverse().unwrap();
+        assert!(back
+            .apply(composed[2])
+            .approx_eq(Coordinate::from((MILLIMETER, MILLIMETER)), APPROX_EQ_MARGIN));
+        assert!(Transform::<f64>::scaling(0.).inverse().is_none());
+    }
 }
@@ -0,0 +1,279 @@
+use gds21::GdsPoint as Gds21Point;
+use log::warn;
+use num::{traits::FloatConst, Float, FromPrimitive};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    rc::Rc,
+};
+
+use crate::{
+    color::LayerData,
+    draw::{
+        bbox::{Bounded, BoundingBox},
+        coordinate::Coordinate,
+    },
+    gds::{ArrayRef, Element, Path, PathType, Polygon, Ref, Text},
+    units::{Absolute, Length, LengthType, Meter, Relative},
+    Num, Quantity,
+};
+
+use super::{DgirCell, DgirLibrary, DgirUnits, Result};
+
+//helper trait to convert database unit values back into lengths, the inverse of `CoordinateIterator`
+pub(crate) trait FromGds21Value: Quantity {
+    type Scale: Copy;
+    type Scalar: Num;
+    fn scale(units: &DgirUnits<Self::Scalar>) -> Self::Scale;
+    fn from_gds21_value(v: i32, scale: Self::Scale) -> Self;
+    fn from_gds21_point(p: &Gds21Point, scale: Self::Scale) -> Coordinate<Self> {
+        Coordinate::from([
+            Self::from_gds21_value(p.x, scale),
+            Self::from_gds21_value(p.y, scale),
+        ])
+    }
+}
+
+impl<T: Num + FromPrimitive> FromGds21Value for Length<Absolute, T> {
+    type Scale = Length<Absolute, T>;
+    type Scalar = T;
+    fn scale(units: &DgirUnits<T>) -> Self::Scale {
+        units.database
+    }
+    fn from_gds21_value(v: i32, scale: Self::Scale) -> Self {
+        scale * T::from_i32(v).unwrap()
+    }
+}
+
+impl<T: Num + FromPrimitive> FromGds21Value for Length<Relative, T> {
+    type Scale = ();
+    type Scalar = T;
+    fn scale(_units: &DgirUnits<T>) -> Self::Scale {}
+    fn from_gds21_value(v: i32, _scale: Self::Scale) -> Self {
+        Length::new_relative::<crate::units::DbUnit>(T::from_i32(v).unwrap())
+    }
+}
+
+fn from_gds21_points<Q: FromGds21Value>(
+    xy: Vec<Gds21Point>,
+    scale: Q::Scale,
+) -> Vec<Coordinate<Q>> {
+    xy.iter().map(|p| Q::from_gds21_point(p, scale)).collect()
+}
+
+//converts a struct into a cell, references are left with empty dependencies
+fn from_gds21_struct<Q: FromGds21Value>(s: gds21::GdsStruct, scale: Q::Scale) -> DgirCell<Q> {
+    use gds21::*;
+    let mut cell = DgirCell::new(s.name);
+    for elem in s.elems {
+        match elem {
+            GdsElement::GdsBoundary(b) => cell.push(Polygon {
+                area: Box::new(from_gds21_points(b.xy, scale).into_iter()),
+                color: LayerData::new(b.layer, b.datatype),
+            }),
+            GdsElement::GdsPath(p) => cell.push(Path {
+                curve: Box::new(from_gds21_points(p.xy, scale).into_iter()),
+                color: LayerData::new(p.layer, p.datatype),
+                width: p.width.map(|w| Q::from_gds21_value(w, scale)),
+                path_type: match p.path_type {
+                    Some(1) => PathType::Round,
+                    Some(2) => PathType::HalfWidth,
+                    Some(4) => PathType::Custom(
+                        Q::from_gds21_value(p.begin_extn.unwrap_or(0), scale),
+                        Q::from_gds21_value(p.end_extn.unwrap_or(0), scale),
+                    ),
+                    _ => PathType::Flush,
+                },
+            }),
+            GdsElement::GdsStructRef(r) => cell.push(Ref {
+                strans: r.strans,
+                pos: Q::from_gds21_point(&r.xy, scale),
+                id: r.name,
+                dep: BTreeSet::new(),
+                extent: None,
+                ports: Vec::new(),
+            }),
+            GdsElement::GdsArrayRef(ar) => cell.push(ArrayRef {
+                strans: ar.strans,
+                rows: ar.cols,
+                cols: ar.rows,
+                start: Q::from_gds21_point(&ar.xy[0], scale),
+                col_end: Q::from_gds21_point(&ar.xy[1], scale),
+                row_end: Q::from_gds21_point(&ar.xy[2], scale),
+                id: ar.name,
+                dep: BTreeSet::new(),
+                extent: None,
+                ports: Vec::new(),
+            }),
+            GdsElement::GdsTextElem(t) => cell.push(Text {
+                content: t.string,
+                strans: t.strans,
+                pos: Q::from_gds21_point(&t.xy, scale),
+                width: t.width.map(|w| Q::from_gds21_value(w, scale)),
+                layer: t.layer,
+                path_type: t.path_type,
+                texttype: t.texttype,
+            }),
+            GdsElement::GdsBox(b) => {
+                warn!(
+                    "box on layer {} of cell {} is imported as polygon",
+                    b.layer, cell.name
+                );
+                cell.push(Polygon {
+                    area: Box::new(from_gds21_points(b.xy.to_vec(), scale).into_iter()),
+                    color: LayerData::new(b.layer, b.boxtype),
+                })
+            }
+            GdsElement::GdsNode(n) => {
+                warn!(
+                    "node on layer {} of cell {} is not supported, skipped",
+                    n.layer, cell.name
+                );
+                &mut cell
+            }
+        };
+    }
+    cell
+}
+
+fn referenced_names(s: &gds21::GdsStruct) -> impl Iterator<Item = &String> {
+    s.elems.iter().filter_map(|e| match e {
+        gds21::GdsElement::GdsStructRef(r) => Some(&r.name),
+        gds21::GdsElement::GdsArrayRef(ar) => Some(&ar.name),
+        _ => None,
+    })
+}
+
+//bounding boxes are filled in from the leaves up, cycles are left to `collect_dependencies` to report
+fn fill_extents<Q>(
+    name: &String,
+    graph: &BTreeMap<String, Vec<String>>,
+    cells: &mut BTreeMap<String, DgirCell<Q>>,
+    visiting: &mut Vec<String>,
+    extents: &mut BTreeMap<String, Option<BoundingBox<Q>>>,
+) where
+    Q: Quantity,
+    DgirCell<Q>: Bounded<Q>,
+{
+    if extents.contains_key(name) || visiting.contains(name) || !cells.contains_key(name) {
+        return;
+    }
+    visiting.push(name.clone());
+    for sub in graph[name].iter() {
+        fill_extents(sub, graph, cells, visiting, extents);
+    }
+    visiting.pop();
+    let cell = cells.get_mut(name).unwrap();
+    for element in cell.elements.iter_mut() {
+        match element {
+            Element::Ref(Ref { id, extent, .. }) | Element::ARef(ArrayRef { id, extent, .. }) => {
+                *extent = extents.get(id).cloned().flatten()
+            }
+            _ => (),
+        }
+    }
+    extents.insert(name.clone(), cell.bounding_box());
+}
+
+//collect `name` and all cells it depends on, cycles are reported as error
+fn collect_dependencies<Q: Quantity>(
+    name: &String,
+    graph: &BTreeMap<String, Vec<String>>,
+    cells: &BTreeMap<String, Rc<DgirCell<Q>>>,
+    visiting: &mut Vec<String>,
+    collected: &mut BTreeSet<Rc<DgirCell<Q>>>,
+) -> Result<()> {
+    if visiting.contains(name) {
+        return Err(format!("circular reference found at cell {}", name).into());
+    }
+    let cell = cells
+        .get(name)
+        .ok_or_else(|| format!("referenced cell {} not found", name))?;
+    if collected.contains(cell) {
+        return Ok(());
+    }
+    collected.insert(cell.clone());
+    visiting.push(name.clone());
+    for sub in graph[name].iter() {
+        collect_dependencies(sub, graph, cells, visiting, collected)?;
+    }
+    visiting.pop();
+    Ok(())
+}
+
+pub(crate) trait FromGds21Library: Sized {
+    fn from_gds21_library(lib: gds21::GdsLibrary) -> Result<Self>;
+}
+
+impl<L, T> FromGds21Library for DgirLibrary<T, Length<L, T>>
+where
+    L: LengthType,
+    T: Num + Float + FloatConst + FromPrimitive,
+    Length<L, T>: FromGds21Value<Scalar = T>,
+{
+    fn from_gds21_library(lib: gds21::GdsLibrary) -> Result<Self> {
+        //`GdsUnits::user_unit` returns the ratio of the two records instead of meters, recover the first record from it
+        let db_in_user = lib.units.user_unit() * lib.units.db_unit();
+        let database = Length::new_absolute::<Meter>(T::from_f64(lib.units.db_unit()).unwrap());
+        let units = DgirUnits {
+            database,
+            user: database / T::from_f64(db_in_user).unwrap(),
+        };
+        let scale = <Length<L, T>>::scale(&units);
+
+        let graph: BTreeMap<String, Vec<String>> = lib
+            .structs
+            .iter()
+            .map(|s| (s.name.clone(), referenced_names(s).cloned().collect()))
+            .collect();
+        let referenced: BTreeSet<&String> = graph.values().flatten().collect();
+        let top_names: Vec<String> = lib
+            .structs
+            .iter()
+            .map(|s| s.name.clone())
+            .filter(|n| !referenced.contains(n))
+            .collect();
+        if top_names.is_empty() && !lib.structs.is_empty() {
+            return Err("no top cell found, circular reference exists".into());
+        }
+
+        let names: Vec<String> = lib.structs.iter().map(|s| s.name.clone()).collect();
+        let mut cells: BTreeMap<_, _> = lib
+            .structs
+            .into_iter()
+            .map(|s| (s.name.clone(), from_gds21_struct::<Length<L, T>>(s, scale)))
+            .collect();
+        let mut extents = BTreeMap::new();
+        for name in names.iter() {
+            fill_extents(name, &graph, &mut cells, &mut Vec::new(), &mut extents);
+        }
+
+        let mut tops = Vec::with_capacity(top_names.len());
+        let mut subs = BTreeMap::new();
+        for name in names {
+            let cell = cells.remove(&name).unwrap();
+            if top_names.contains(&name) {
+                tops.push(cell);
+            } else {
+                subs.insert(name, Rc::new(cell));
+            }
+        }
+
+        //dependencies are flattened into references of top cells, the same as `DgirCell::into_ref` does
+        for top in tops.iter_mut() {
+            for element in top.elements.iter_mut() {
+                match element {
+                    Element::Ref(Ref { id, dep, .. }) | Element::ARef(ArrayRef { id, dep, .. }) => {
+                        collect_dependencies(id, &graph, &subs, &mut Vec::new(), dep)?
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        Ok(DgirLibrary {
+            name: Some(lib.name),
+            units,
+            cells: tops,
+        })
+    }
+}
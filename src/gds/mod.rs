@@ -1,27 +1,51 @@
-use std::{collections::BTreeSet, fmt::Debug, rc::Rc};
+use std::{collections::BTreeSet, fmt::Debug, marker::PhantomData, rc::Rc};
 
 use gds21::GdsStrans;
-use num::{traits::FloatConst, FromPrimitive, ToPrimitive};
+use num::{traits::FloatConst, Float, FromPrimitive, ToPrimitive};
 
 use crate::{
-    color::LayerData,
-    draw::coordinate::Coordinate,
+    color::{Decorated, LayerData},
+    cursor::Cursor,
+    draw::{
+        bbox::{Bounded, BoundingBox},
+        boolean::{Boolean, BooleanOp},
+        coordinate::{Coordinate, LenCo},
+        curve::{groups::Group, Area},
+        offset::{path_outline, JoinStyle, Offset},
+        Resolution,
+    },
     units::{Absolute, AbsoluteLength, Angle, Length, LengthType, Relative},
-    Num, Quantity,
+    zero, Num, Quantity,
 };
 
-use self::togds::ToGds21Library;
+use self::{fromgds::FromGds21Library, togds::ToGds21Library};
 
+mod fromgds;
 mod togds;
 
 //const DISPLAY_POINTS_NUM: usize = 20;
 type Result<T> = gds21::GdsResult<T>;
 type Points<Q> = Box<dyn Iterator<Item = Coordinate<Q>>>;
 
+//how the ends of a path are drawn, the same as the GDSII path types
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum PathType<Q: Quantity> {
+    //ends square at the first and last points
+    #[default]
+    Flush,
+    //half circle around the first and last points
+    Round,
+    //square ends extended by half the width
+    HalfWidth,
+    //square ends extended by the given lengths at the begin and the end
+    Custom(Q, Q),
+}
+
 pub struct Path<Q: Quantity> {
     pub curve: Points<Q>,
     pub color: LayerData,
     pub width: Option<Q>,
+    pub path_type: PathType<Q>,
 }
 
 impl<Q> Debug for Path<Q>
@@ -31,12 +55,63 @@ where
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "Path {{ curve: ..., (layer, datatype): {}, width: {:?} }}",
-            self.color, self.width
+            "Path {{ curve: ..., (layer, datatype): {}, width: {:?}, path_type: {:?} }}",
+            self.color, self.width, self.path_type
         )
     }
 }
 
+impl<L, T> Path<Length<L, T>>
+where
+    L: LengthType,
+    T: Num + Float + FloatConst + FromPrimitive,
+{
+    //lengths added to the begin and the end by the path type
+    fn extension(&self, half: Length<L, T>) -> (Length<L, T>, Length<L, T>) {
+        match self.path_type {
+            PathType::Flush | PathType::Round => (zero(), zero()),
+            PathType::HalfWidth => (half, half),
+            PathType::Custom(begin, end) => (begin, end),
+        }
+    }
+    //outline with mitered joints, `res` is only used by round ends, a path without width or with less than two
+    //points gives `None`
+    pub fn to_polygon(self, res: Resolution<Length<L, T>>) -> Option<Polygon<Length<L, T>>> {
+        let half = self.width?.abs() / (T::one() + T::one());
+        let extension = self.extension(half);
+        let round = (self.path_type == PathType::Round).then_some(res);
+        let points = self.curve.map(|c| (c[0].value, c[1].value)).collect();
+        let outline = path_outline(
+            points,
+            half.value,
+            (extension.0.value, extension.1.value),
+            round,
+        );
+        if outline.is_empty() {
+            return None;
+        }
+        let area: Vec<_> = outline
+            .into_iter()
+            .map(|(x, y)| {
+                Coordinate::from([
+                    Length {
+                        value: x,
+                        marker: PhantomData,
+                    },
+                    Length {
+                        value: y,
+                        marker: PhantomData,
+                    },
+                ])
+            })
+            .collect();
+        Some(Polygon {
+            area: Box::new(area.into_iter()),
+            color: self.color,
+        })
+    }
+}
+
 pub struct Polygon<Q: Quantity> {
     pub area: Points<Q>,
     pub color: LayerData,
@@ -60,9 +135,17 @@ where
     pub(crate) pos: Coordinate<Q>,
     pub(crate) id: String,
     pub(crate) dep: BTreeSet<Rc<DgirCell<Q>>>, //TODO need to avoid circular ref, or dead loop happens
+    pub(crate) extent: Option<BoundingBox<Q>>, //bounding box of the referenced cell, without any transform
+    pub(crate) ports: Vec<Port<Q>>,            //ports of the referenced cell, without any transform
 }
 
 impl<Q: Quantity> Ref<Q> {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+    pub fn pos(&self) -> Coordinate<Q> {
+        self.pos.clone()
+    }
     pub fn set_pos<C: Into<Coordinate<Q>>>(&mut self, c: C) -> &mut Self {
         self.pos = c.into();
         self
@@ -79,6 +162,14 @@ impl<Q: Quantity> Ref<Q> {
         }
         self
     }
+    //reflection about the x axis of the cell, applied before the rotation
+    pub fn set_mirror(&mut self, mirror: bool) -> &mut Self {
+        self.strans.get_or_insert_with(Default::default).reflected = mirror;
+        self
+    }
+    pub fn mirrored(&self) -> bool {
+        self.strans.as_ref().is_some_and(|s| s.reflected)
+    }
     pub fn into_array_ref(
         &self,
         start: impl Into<Coordinate<Q>>,
@@ -96,6 +187,8 @@ impl<Q: Quantity> Ref<Q> {
             row_end: row_end.into(),
             id: self.id.clone(),
             dep: self.dep.clone(),
+            extent: self.extent.clone(),
+            ports: self.ports.clone(),
         }
     }
 }
@@ -113,9 +206,14 @@ where
     pub(crate) row_end: Coordinate<Q>,
     pub(crate) id: String,
     pub(crate) dep: BTreeSet<Rc<DgirCell<Q>>>, //TODO need to avoid circular ref, or dead loop happens
+    pub(crate) extent: Option<BoundingBox<Q>>,
+    pub(crate) ports: Vec<Port<Q>>,
 }
 
 impl<Q: Quantity> ArrayRef<Q> {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
     pub fn set_start<C: Into<Coordinate<Q>>>(&mut self, c: C) -> &mut Self {
         self.start = c.into();
         self
@@ -140,6 +238,14 @@ impl<Q: Quantity> ArrayRef<Q> {
         }
         self
     }
+    //reflection about the x axis of the cell, applied before the rotation
+    pub fn set_mirror(&mut self, mirror: bool) -> &mut Self {
+        self.strans.get_or_insert_with(Default::default).reflected = mirror;
+        self
+    }
+    pub fn mirrored(&self) -> bool {
+        self.strans.as_ref().is_some_and(|s| s.reflected)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -172,6 +278,53 @@ impl<Q: Quantity> Text<Q> {
     }
 }
 
+//interface point of a cell, `dir` points out of the cell, ports are not written to GDSII
+#[derive(Clone, Debug)]
+pub struct Port<Q: Quantity> {
+    pub(crate) name: String,
+    pub(crate) pos: Coordinate<Q>,
+    pub(crate) dir: Angle<Q::Num>,
+    pub(crate) width: Q,
+    pub(crate) layers: Group<LayerData>,
+}
+
+impl<Q: Quantity> Port<Q> {
+    pub fn new(
+        name: impl ToString,
+        pos: impl Into<Coordinate<Q>>,
+        dir: Angle<Q::Num>,
+        width: Q,
+        layers: impl Into<Group<LayerData>>,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            pos: pos.into(),
+            dir,
+            width,
+            layers: layers.into(),
+        }
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn pos(&self) -> Coordinate<Q> {
+        self.pos.clone()
+    }
+    pub fn dir(&self) -> Angle<Q::Num> {
+        self.dir
+    }
+    pub fn width(&self) -> Q {
+        self.width.clone()
+    }
+    pub fn layers(&self) -> &Group<LayerData> {
+        &self.layers
+    }
+    pub fn rename(&mut self, name: impl ToString) -> &mut Self {
+        self.name = name.to_string();
+        self
+    }
+}
+
 #[derive(Debug)]
 pub enum Element<Q>
 where
@@ -192,6 +345,7 @@ where
         DgirCell {
             name: name.to_string(),
             elements: vec![self],
+            ports: Vec::new(),
         }
     }
 }
@@ -350,6 +504,7 @@ where
 {
     pub name: String,
     pub(crate) elements: Vec<Element<Q>>,
+    pub(crate) ports: Vec<Port<Q>>,
 }
 
 impl<Q: Quantity> AsMut<DgirCell<Q>> for DgirCell<Q> {
@@ -366,6 +521,7 @@ where
         Self {
             name: name.to_string(),
             elements: Vec::new(),
+            ports: Vec::new(),
         }
     }
     pub fn rename(&mut self, name: String) -> &mut Self {
@@ -379,10 +535,28 @@ where
         }
         self
     }
+    //a port with the same name is replaced
+    pub fn add_port(&mut self, port: Port<Q>) -> &mut Self {
+        self.ports.retain(|p| p.name != port.name);
+        self.ports.push(port);
+        self
+    }
+    pub fn ports(&self) -> &[Port<Q>] {
+        &self.ports
+    }
+    pub fn port(&self, name: &str) -> Option<&Port<Q>> {
+        self.ports.iter().find(|p| p.name == name)
+    }
 
-    pub fn into_ref(self) -> Ref<Q> {
+    //the bounding box is taken before the cell is shared, since its points can't be read behind `Rc`
+    pub fn into_ref(self) -> Ref<Q>
+    where
+        Self: Bounded<Q>,
+    {
         let mut s = self;
         let name = s.name.clone();
+        let extent = s.bounding_box();
+        let ports = s.ports.clone();
         let mut dep = s.get_dependencies();
         dep.insert(Rc::new(s));
         Ref {
@@ -390,11 +564,18 @@ where
             dep,
             pos: Coordinate::from([Q::zero(), Q::zero()]),
             id: name,
+            extent,
+            ports,
         }
     }
-    pub fn into_ref_at(self, pos: impl Into<Coordinate<Q>>) -> Ref<Q> {
+    pub fn into_ref_at(self, pos: impl Into<Coordinate<Q>>) -> Ref<Q>
+    where
+        Self: Bounded<Q>,
+    {
         let mut s = self;
         let name = s.name.clone();
+        let extent = s.bounding_box();
+        let ports = s.ports.clone();
         let mut dep = s.get_dependencies();
         dep.insert(Rc::new(s));
         Ref {
@@ -402,6 +583,8 @@ where
             dep,
             pos: pos.into(),
             id: name,
+            extent,
+            ports,
         }
     }
     pub fn into_array_ref(
@@ -411,8 +594,13 @@ where
         row_end: impl Into<Coordinate<Q>>,
         cols: i16,
         col_end: impl Into<Coordinate<Q>>,
-    ) -> ArrayRef<Q> {
+    ) -> ArrayRef<Q>
+    where
+        Self: Bounded<Q>,
+    {
         let mut s = self;
+        let extent = s.bounding_box();
+        let ports = s.ports.clone();
         let mut dep = s.get_dependencies();
         let name = s.name.clone();
         dep.insert(Rc::new(s));
@@ -425,7 +613,28 @@ where
             id: name,
             dep,
             strans: None,
+            extent,
+            ports,
+        }
+    }
+    pub(crate) fn layer_polygons(&mut self, layer: LayerData) -> Group<Area<Vec<Coordinate<Q>>>> {
+        let mut ret = Vec::new();
+        for element in self.elements.iter_mut() {
+            if let Element::Polygon(p) = element {
+                if p.color == layer {
+                    let area: Vec<_> =
+                        std::mem::replace(&mut p.area, Box::new(std::iter::empty())).collect();
+                    p.area = Box::new(area.clone().into_iter());
+                    ret.push(Area { area });
+                }
+            }
         }
+        Group(ret)
+    }
+    pub(crate) fn remove_layer_polygons(&mut self, layer: LayerData) -> &mut Self {
+        self.elements
+            .retain(|e| !matches!(e, Element::Polygon(p) if p.color == layer));
+        self
     }
     //make sure every sub dependencies is empty
     pub(crate) fn get_dependencies(&mut self) -> BTreeSet<Rc<DgirCell<Q>>> {
@@ -447,6 +656,296 @@ where
     }
 }
 
+impl<L: LengthType, T: Num + Float> DgirCell<Length<L, T>> {
+    //polygons on layer `a` and `b` are kept unless one of them is `target`, in which case they are replaced by the result
+    pub fn boolean(
+        &mut self,
+        a: LayerData,
+        b: LayerData,
+        op: BooleanOp,
+        target: LayerData,
+    ) -> &mut Self {
+        let subject = self.layer_polygons(a);
+        let clip = self.layer_polygons(b);
+        if a == target || b == target {
+            self.remove_layer_polygons(target);
+        }
+        self.push(subject.boolean(clip, op).color(target))
+    }
+    pub fn merge_layer(&mut self, layer: LayerData) -> &mut Self {
+        self.boolean(layer, layer, BooleanOp::Union, layer)
+    }
+    //paths with width are replaced by their outlines, for mask shops which don't accept paths
+    pub fn paths_to_polygons(&mut self, res: Resolution<Length<L, T>>) -> &mut Self
+    where
+        T: FloatConst + FromPrimitive,
+    {
+        self.elements = std::mem::take(&mut self.elements)
+            .into_iter()
+            .filter_map(|e| match e {
+                Element::Path(p) if p.width.is_some() => p.to_polygon(res).map(Element::Polygon),
+                e => Some(e),
+            })
+            .collect();
+        self
+    }
+    //polygons on `layer` are kept unless it is `target`
+    pub fn offset(
+        &mut self,
+        layer: LayerData,
+        delta: Length<L, T>,
+        join: JoinStyle<L, T>,
+        target: LayerData,
+    ) -> &mut Self
+    where
+        T: FromPrimitive,
+    {
+        let source = self.layer_polygons(layer);
+        if layer == target {
+            self.remove_layer_polygons(target);
+        }
+        self.push(source.offset(delta, join).color(target))
+    }
+}
+
+//reflection about the x axis first, then magnification and rotation, the same order as GDSII applies them
+fn strans_point<L, T>(strans: &Option<GdsStrans>, p: LenCo<L, T>) -> LenCo<L, T>
+where
+    L: LengthType,
+    T: Num + Float + FromPrimitive,
+{
+    let Some(s) = strans else {
+        return p;
+    };
+    let (x, y) = (p[0], if s.reflected { -p[1] } else { p[1] });
+    let mag = s.mag.map(|m| T::from_f64(m).unwrap()).unwrap_or(T::one());
+    let ang = T::from_f64(s.angle.unwrap_or(0.).to_radians()).unwrap();
+    let (cos, sin) = (ang.cos() * mag, ang.sin() * mag);
+    Coordinate::from([x * cos - y * sin, x * sin + y * cos])
+}
+
+fn strans_dir<T: Num + Float + FromPrimitive>(
+    strans: &Option<GdsStrans>,
+    dir: Angle<T>,
+) -> Angle<T> {
+    let Some(s) = strans else {
+        return dir;
+    };
+    let dir = if s.reflected { -dir } else { dir };
+    dir + Angle::from_rad(T::from_f64(s.angle.unwrap_or(0.).to_radians()).unwrap())
+}
+
+impl<L, T> Port<Length<L, T>>
+where
+    L: LengthType,
+    T: Num + Float + FromPrimitive,
+{
+    pub(crate) fn transform(&self, strans: &Option<GdsStrans>, offset: LenCo<L, T>) -> Self {
+        let mag = strans.as_ref().and_then(|s| s.mag).unwrap_or(1.);
+        Self {
+            name: self.name.clone(),
+            pos: strans_point(strans, self.pos) + offset,
+            dir: strans_dir(strans, self.dir),
+            width: self.width * T::from_f64(mag.abs()).unwrap(),
+            layers: self.layers.clone(),
+        }
+    }
+    //the same port facing the opposite direction, e.g. an input taken from where a cursor stopped
+    pub fn rev(mut self) -> Self
+    where
+        T: FloatConst,
+    {
+        self.dir = self.dir + Angle::from_rad(T::PI());
+        self
+    }
+}
+
+impl<L, T> Ref<Length<L, T>>
+where
+    L: LengthType,
+    T: Num + Float + FromPrimitive,
+{
+    //ports of the referenced cell, moved along with the reference
+    pub fn ports(&self) -> Vec<Port<Length<L, T>>> {
+        self.ports
+            .iter()
+            .map(|p| p.transform(&self.strans, self.pos))
+            .collect()
+    }
+    pub fn port(&self, name: &str) -> Option<Port<Length<L, T>>> {
+        self.ports
+            .iter()
+            .find(|p| p.name == name)
+            .map(|p| p.transform(&self.strans, self.pos))
+    }
+    //moves and rotates the reference so that `port` sits at `target` facing against it, reflection and
+    //magnification are kept, `None` if the cell has no such port and the reference is left as it is
+    pub fn connect(&mut self, port: &str, target: &Cursor<L, T>) -> Option<&mut Self>
+    where
+        T: FloatConst,
+    {
+        let p = self.ports.iter().find(|p| p.name == port).cloned()?;
+        let reflected = self.mirrored();
+        let dir = if reflected { -p.dir } else { p.dir };
+        let rot = (target.dir - dir).to_rad() + T::PI();
+        let full = T::PI() + T::PI();
+        self.set_rot(Angle::from_rad(rot - Float::floor(rot / full) * full));
+        let placed = p.transform(&self.strans, Coordinate::from([zero(), zero()]));
+        self.pos = target.pos - placed.pos;
+        Some(self)
+    }
+    //corners of the bounding box of the referenced cell, moved along with the reference
+    pub(crate) fn extent_corners(&self) -> Option<[LenCo<L, T>; 4]> {
+        Some(
+            self.extent?
+                .corners()
+                .map(|p| strans_point(&self.strans, p) + self.pos),
+        )
+    }
+}
+
+impl<L, T> ArrayRef<Length<L, T>>
+where
+    L: LengthType,
+    T: Num + Float + FromPrimitive,
+{
+    //`row` counts along `col_end` and `col` along `row_end`, the same as the GDSII record is written
+    pub(crate) fn instance_offset(&self, row: i16, col: i16) -> LenCo<L, T> {
+        let step = |end: LenCo<L, T>, n: i16, i: i16| {
+            let k = T::from_i16(i).unwrap() / T::from_i16(n.max(1)).unwrap();
+            Coordinate::from([(end[0] - self.start[0]) * k, (end[1] - self.start[1]) * k])
+        };
+        self.start + step(self.col_end, self.rows, row) + step(self.row_end, self.cols, col)
+    }
+    //ports of the instance at `row` and `col`
+    pub fn ports(&self, row: i16, col: i16) -> Vec<Port<Length<L, T>>> {
+        let offset = self.instance_offset(row, col);
+        self.ports
+            .iter()
+            .map(|p| p.transform(&self.strans, offset))
+            .collect()
+    }
+    pub fn port(&self, name: &str, row: i16, col: i16) -> Option<Port<Length<L, T>>> {
+        let offset = self.instance_offset(row, col);
+        self.ports
+            .iter()
+            .find(|p| p.name == name)
+            .map(|p| p.transform(&self.strans, offset))
+    }
+    //corners of the bounding box of every instance
+    pub(crate) fn extent_corners(&self) -> Vec<[LenCo<L, T>; 4]> {
+        let Some(extent) = self.extent else {
+            return Vec::new();
+        };
+        let corners = extent.corners().map(|p| strans_point(&self.strans, p));
+        (0..self.rows.max(1))
+            .flat_map(|i| (0..self.cols.max(1)).map(move |j| (i, j)))
+            .map(|(i, j)| {
+                let o = self.instance_offset(i, j);
+                corners.map(|p| p + o)
+            })
+            .collect()
+    }
+}
+
+impl<L, T> Bounded<Length<L, T>> for Element<Length<L, T>>
+where
+    L: LengthType,
+    T: Num + Float + FloatConst + FromPrimitive,
+{
+    fn bounding_box(&mut self) -> Option<BoundingBox<Length<L, T>>> {
+        match self {
+            Element::Path(p) => {
+                let curve: Vec<_> =
+                    std::mem::replace(&mut p.curve, Box::new(std::iter::empty())).collect();
+                p.curve = Box::new(curve.clone().into_iter());
+                let b = BoundingBox::from_points(curve.clone())?;
+                let Some(w) = p.width else {
+                    return Some(b);
+                };
+                //negative widths are absolute widths in GDSII
+                let half = w.abs() / (T::one() + T::one());
+                let (begin, end) = p.extension(half);
+                //the flush outline holds the extensions and the miters
+                let outline = path_outline::<L, T>(
+                    curve.iter().map(|c| (c[0].value, c[1].value)).collect(),
+                    half.value,
+                    (begin.value, end.value),
+                    None,
+                );
+                let outline = BoundingBox::from_points(outline.into_iter().map(|(x, y)| {
+                    Coordinate::from([
+                        Length {
+                            value: x,
+                            marker: PhantomData,
+                        },
+                        Length {
+                            value: y,
+                            marker: PhantomData,
+                        },
+                    ])
+                }));
+                //round ends and a path too short for an outline stay within half the width of the points
+                let near =
+                    (p.path_type == PathType::Round || outline.is_none()).then(|| b.expand(half));
+                BoundingBox::merge(outline, near)
+            }
+            Element::Polygon(p) => {
+                let area: Vec<_> =
+                    std::mem::replace(&mut p.area, Box::new(std::iter::empty())).collect();
+                p.area = Box::new(area.clone().into_iter());
+                BoundingBox::from_points(area)
+            }
+            Element::Ref(r) => r.bounding_box(),
+            Element::ARef(ar) => ar.bounding_box(),
+            Element::Text(t) => Some(BoundingBox::new(t.pos, t.pos)),
+        }
+    }
+}
+
+impl<L, T> Bounded<Length<L, T>> for Ref<Length<L, T>>
+where
+    L: LengthType,
+    T: Num + Float + FromPrimitive,
+{
+    fn bounding_box(&mut self) -> Option<BoundingBox<Length<L, T>>> {
+        let extent = self.extent?;
+        Some(extent.transform(|p| strans_point(&self.strans, p) + self.pos))
+    }
+}
+
+impl<L, T> Bounded<Length<L, T>> for ArrayRef<Length<L, T>>
+where
+    L: LengthType,
+    T: Num + Float + FromPrimitive,
+{
+    //union of the instances at the four corners of the lattice
+    fn bounding_box(&mut self) -> Option<BoundingBox<Length<L, T>>> {
+        let extent = self.extent?.transform(|p| strans_point(&self.strans, p));
+        let (rows, cols) = ((self.rows - 1).max(0), (self.cols - 1).max(0));
+        [(0, 0), (rows, 0), (0, cols), (rows, cols)]
+            .into_iter()
+            .map(|(i, j)| {
+                let o = self.instance_offset(i, j);
+                extent.transform(|p| p + o)
+            })
+            .reduce(BoundingBox::union)
+    }
+}
+
+impl<L, T> Bounded<Length<L, T>> for DgirCell<Length<L, T>>
+where
+    L: LengthType,
+    T: Num + Float + FloatConst + FromPrimitive,
+{
+    fn bounding_box(&mut self) -> Option<BoundingBox<Length<L, T>>> {
+        self.elements
+            .iter_mut()
+            .map(|e| e.bounding_box())
+            .fold(None, BoundingBox::merge)
+    }
+}
+
 impl<T: Num + FromPrimitive + ToPrimitive> DgirCell<Length<Absolute, T>> {
     pub fn save_as_lib(self, filename: impl AsRef<std::path::Path>) -> Result<()> {
         DgirLibrary {
@@ -534,6 +1033,15 @@ where
     user: Length<Absolute, T>,
 }
 
+impl<T: Num> DgirUnits<T> {
+    pub fn database(&self) -> Length<Absolute, T> {
+        self.database
+    }
+    pub fn user(&self) -> Length<Absolute, T> {
+        self.user
+    }
+}
+
 impl<T> Default for DgirUnits<T>
 where
     T: Num + FromPrimitive,
@@ -594,6 +1102,15 @@ where
         self.cells.push(cell.into());
         self
     }
+    pub fn units(&self) -> DgirUnits<T> {
+        self.units
+    }
+    pub fn cells(&self) -> &[DgirCell<Length<L, T>>] {
+        &self.cells
+    }
+    pub fn into_cells(self) -> Vec<DgirCell<Length<L, T>>> {
+        self.cells
+    }
 }
 
 impl<T> DgirLibrary<T, Length<Absolute, T>>
@@ -603,6 +1120,12 @@ where
     pub fn save(self, filename: impl AsRef<std::path::Path>) -> Result<()> {
         self.to_gds21_library().save(filename)
     }
+    pub fn load(filename: impl AsRef<std::path::Path>) -> Result<Self>
+    where
+        T: Float + FloatConst,
+    {
+        Self::from_gds21_library(gds21::GdsLibrary::load(filename)?)
+    }
 }
 
 impl<T> DgirLibrary<T, Length<Relative, T>>
@@ -612,4 +1135,10 @@ where
     pub fn save(self, filename: impl AsRef<std::path::Path>) -> Result<()> {
         self.to_gds21_library().save(filename)
     }
+    pub fn load(filename: impl AsRef<std::path::Path>) -> Result<Self>
+    where
+        T: Float + FloatConst,
+    {
+        Self::from_gds21_library(gds21::GdsLibrary::load(filename)?)
+    }
 }
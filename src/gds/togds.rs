@@ -8,10 +8,23 @@ use crate::{
     gds::Element,
     points_num_check, split_path, split_polygon,
     units::{Absolute, Length, Relative},
-    Num, MAX_POINTS_NUM,
+    Num, Quantity, MAX_POINTS_NUM,
 };
 
-use super::DgirCell;
+use super::{DgirCell, PathType};
+
+//flush is the default path type, so its record is left out
+fn path_type_record<Q: Quantity>(
+    path_type: PathType<Q>,
+    to_i32: impl Fn(Q) -> Option<i32>,
+) -> (Option<i16>, Option<i32>, Option<i32>) {
+    match path_type {
+        PathType::Flush => (None, None, None),
+        PathType::Round => (Some(1), None, None),
+        PathType::HalfWidth => (Some(2), None, None),
+        PathType::Custom(begin, end) => (Some(4), to_i32(begin), to_i32(end)),
+    }
+}
 
 pub(crate) trait ToGds21Points: Iterator {
     type Scale: Clone;
@@ -95,6 +108,8 @@ where
             match elem {
                 Element::Path(p) => {
                     let xy = p.curve.to_gds21_points(scale);
+                    let (path_type, begin_extn, end_extn) =
+                        path_type_record(p.path_type, |l| (l / scale).to_i32());
                     if points_num_check(&xy) {
                         new_cell.elems.push(GdsElement::GdsPath({
                             GdsPath {
@@ -105,6 +120,9 @@ where
                                     Some(l) => (l / scale).to_i32(),
                                     None => None,
                                 },
+                                path_type,
+                                begin_extn,
+                                end_extn,
                                 ..Default::default()
                             }
                         }))
@@ -113,13 +131,19 @@ where
                             Some(l) => (l / scale).to_i32(),
                             None => None,
                         };
-                        for c in split_path(xy, MAX_POINTS_NUM) {
+                        let pieces = split_path(xy, MAX_POINTS_NUM);
+                        let last = pieces.len() - 1;
+                        //the extensions only apply to the real ends of the path
+                        for (i, c) in pieces.into_iter().enumerate() {
                             new_cell.elems.push(GdsElement::GdsPath({
                                 GdsPath {
                                     layer: p.color.layer,
                                     datatype: p.color.datatype,
                                     xy: c,
                                     width,
+                                    path_type,
+                                    begin_extn: if i == 0 { begin_extn } else { None },
+                                    end_extn: if i == last { end_extn } else { None },
                                     ..Default::default()
                                 }
                             }))
@@ -208,6 +232,8 @@ where
             match elem {
                 Element::Path(p) => {
                     let xy = p.curve.to_gds21_points(scale);
+                    let (path_type, begin_extn, end_extn) =
+                        path_type_record(p.path_type, |l| l.value.to_i32());
                     if points_num_check(&xy) {
                         new_cell.elems.push(GdsElement::GdsPath({
                             GdsPath {
@@ -218,6 +244,9 @@ where
                                     Some(l) => l.value.to_i32(),
                                     None => None,
                                 },
+                                path_type,
+                                begin_extn,
+                                end_extn,
                                 ..Default::default()
                             }
                         }))
@@ -226,13 +255,19 @@ where
                             Some(l) => l.value.to_i32(),
                             None => None,
                         };
-                        for c in split_path(xy, MAX_POINTS_NUM) {
+                        let pieces = split_path(xy, MAX_POINTS_NUM);
+                        let last = pieces.len() - 1;
+                        //the extensions only apply to the real ends of the path
+                        for (i, c) in pieces.into_iter().enumerate() {
                             new_cell.elems.push(GdsElement::GdsPath({
                                 GdsPath {
                                     layer: p.color.layer,
                                     datatype: p.color.datatype,
                                     xy: c,
                                     width,
+                                    path_type,
+                                    begin_extn: if i == 0 { begin_extn } else { None },
+                                    end_extn: if i == last { end_extn } else { None },
                                     ..Default::default()
                                 }
                             }))
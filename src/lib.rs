@@ -14,9 +14,11 @@ use units::{AbsoluteLength, Length, LengthType};
 pub use num;
 
 pub mod color;
+pub mod cross_section;
 pub mod cursor;
 pub mod draw;
 pub mod gds;
+pub mod route;
 pub mod units;
 
 pub trait Num:
@@ -28,6 +30,7 @@ pub trait Num:
     + num::traits::NumAssignRef
     + num::traits::Signed
     + num::traits::ToPrimitive
+    + Scalar<Num = Self>
 {
 }
 
@@ -40,12 +43,33 @@ impl<T> Num for T where
         + num::traits::NumAssignRef
         + num::traits::Signed
         + num::traits::ToPrimitive
+        + Scalar<Num = T>
 {
 }
 
-pub trait Quantity: 'static + Clone + Debug + num::Zero + PartialEq + PartialOrd {}
+pub trait Quantity: 'static + Clone + Debug + num::Zero + PartialEq + PartialOrd + Scalar {}
 
-impl<T> Quantity for T where T: 'static + Clone + Debug + num::Zero + PartialEq + PartialOrd {}
+impl<T> Quantity for T where T: 'static + Clone + Debug + num::Zero + PartialEq + PartialOrd + Scalar
+{}
+
+//the number a quantity is counted in, e.g. the angles next to a `Length<L, T>` are `Angle<T>`
+pub trait Scalar {
+    type Num: 'static + Copy + Debug + PartialOrd;
+}
+
+impl<L: LengthType, T: 'static + Copy + Debug + PartialOrd> Scalar for Length<L, T> {
+    type Num = T;
+}
+
+macro_rules! scalar_of_itself {
+    ($($t:ty),*) => {
+        $(impl Scalar for $t {
+            type Num = $t;
+        })*
+    };
+}
+
+scalar_of_itself!(f32, f64, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
 
 const MAX_POINTS_NUM: usize = 8191;
 
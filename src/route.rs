@@ -0,0 +1,1330 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap},
+    f64::consts::{FRAC_1_SQRT_2, FRAC_PI_2, PI, TAU},
+    fmt::{self, Display},
+    iter::once,
+    marker::PhantomData,
+    mem,
+    ops::Range,
+};
+
+use log::warn;
+use nalgebra::RealField;
+use num::{traits::FloatConst, Float, FromPrimitive};
+
+use crate::{
+    color::LayerData,
+    cross_section::Profile,
+    cursor::{Assembler, Cursor},
+    draw::coordinate::{Coordinate, LenCo},
+    gds::{DgirCell, Element},
+    units::{Angle, Length, LengthType},
+    Num,
+};
+
+//lengths in the units of the assembler and angles in radians below this are treated as zero, the routes are planned
+//in `f64` whatever the scalar of the lengths
+const TOLERANCE: f64 = 1e-9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteError {
+    //the assembler has no bend radius, see `Assembler::set_radius`
+    NoRadius,
+    //the target direction is not a multiple of 90 degrees from the cursor direction
+    NotManhattan,
+    //the first segment does not leave along the cursor direction
+    Misaligned,
+    //a segment is shorter than the bends at its ends
+    TooShort,
+    //no route of up to four quarter turns reaches the target with the bend radius
+    Unreachable,
+    //the obstacles leave no way to the target
+    NoRoute,
+    //the numbers of the starts and the ends of a bundle differ
+    Unpaired,
+    //the starts or the ends of a bundle do not share one direction
+    NotParallel,
+    //the order across a bundle differs between the starts and the ends
+    Crossing,
+    //the route is already longer than the target length
+    TooLong,
+    //the delay line does not fit in its region
+    NoRoom,
+}
+
+impl Display for RouteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RouteError::NoRadius => write!(f, "no bend radius is set"),
+            RouteError::NotManhattan => write!(f, "the target is not in a manhattan direction"),
+            RouteError::Misaligned => write!(f, "the route does not start along the cursor"),
+            RouteError::TooShort => write!(f, "a segment is too short for its bends"),
+            RouteError::Unreachable => write!(f, "the target is out of reach of the bends"),
+            RouteError::NoRoute => write!(f, "no route around the obstacles is found"),
+            RouteError::Unpaired => write!(f, "the bundle has unpaired starts or ends"),
+            RouteError::NotParallel => write!(f, "the bundle starts or ends are not parallel"),
+            RouteError::Crossing => write!(f, "the waveguides of the bundle would cross"),
+            RouteError::TooLong => write!(f, "the route is longer than the target length"),
+            RouteError::NoRoom => write!(f, "the delay line does not fit in its region"),
+        }
+    }
+}
+
+impl std::error::Error for RouteError {}
+
+fn length<L: LengthType, T: Num + FromPrimitive>(value: f64) -> Length<L, T> {
+    Length {
+        value: T::from_f64(value).unwrap(),
+        marker: PhantomData,
+    }
+}
+
+fn value<L: LengthType, T: Num>(l: Length<L, T>) -> f64 {
+    l.value.to_f64().unwrap()
+}
+
+fn angle<T: Num + FromPrimitive>(rad: f64) -> Angle<T> {
+    Angle::from_rad(T::from_f64(rad).unwrap())
+}
+
+fn radians<T: Num + FloatConst + FromPrimitive>(a: Angle<T>) -> f64 {
+    a.to_rad().to_f64().unwrap()
+}
+
+//to (-pi, pi]
+fn normalize(a: f64) -> f64 {
+    let a = (a + PI).rem_euclid(TAU) - PI;
+    if a <= -PI {
+        a + TAU
+    } else {
+        a
+    }
+}
+
+//corner to corner lengths of a route starting at the origin towards +x, `turns` are quarter turns, 1 to the left
+//and -1 to the right, every segment is long enough for the bends at its ends
+fn plan(turns: &[i32], (x, y): (f64, f64), radius: f64) -> Option<Vec<f64>> {
+    let k = turns.len();
+    let mut lens: Vec<f64> = (0..=k)
+        .map(|i| {
+            if k == 0 {
+                0.
+            } else if i == 0 || i == k {
+                radius
+            } else {
+                radius * 2.
+            }
+        })
+        .collect();
+    let headings: Vec<i32> = once(0)
+        .chain(turns.iter().scan(0, |h, t| {
+            *h = (*h + t).rem_euclid(4);
+            Some(*h)
+        }))
+        .collect();
+    //the axes are solved apart, the missing length goes to the first segment heading the right way
+    let sign = |h: i32| if h < 2 { 1. } else { -1. };
+    for (axis, target) in [(0, x), (1, y)] {
+        let base: f64 = lens
+            .iter()
+            .zip(headings.iter())
+            .filter(|(_, h)| *h % 2 == axis)
+            .map(|(l, h)| l * sign(*h))
+            .sum();
+        let extra = target - base;
+        if extra.abs() > TOLERANCE {
+            let i = headings
+                .iter()
+                .position(|h| h % 2 == axis && sign(*h) * extra > 0.)?;
+            lens[i] += extra.abs();
+        }
+    }
+    Some(lens)
+}
+
+//corners and quarter turns of a manhattan route
+type Manhattan = (Vec<(f64, f64)>, Vec<i32>);
+
+//corners of the manhattan route from the origin towards +x to `target` with `q` quarter turns in total, the last
+//corner is the target, fewest turns first and then the shortest
+fn manhattan(target: (f64, f64), q: i32, radius: f64) -> Option<Manhattan> {
+    let mut best: Option<(f64, Vec<i32>, Vec<f64>)> = None;
+    for k in 0..=4 {
+        for mask in 0..1 << k {
+            let turns: Vec<i32> = (0..k)
+                .map(|i| if mask >> i & 1 == 0 { 1 } else { -1 })
+                .collect();
+            if turns.iter().sum::<i32>().rem_euclid(4) != q {
+                continue;
+            }
+            if let Some(lens) = plan(&turns, target, radius) {
+                let cost = lens.iter().sum::<f64>();
+                if !best.as_ref().is_some_and(|b| cost >= b.0 - TOLERANCE) {
+                    best = Some((cost, turns, lens));
+                }
+            }
+        }
+        if best.is_some() {
+            break;
+        }
+    }
+    let (_, turns, lens) = best?;
+    let mut corner = (0., 0.);
+    let mut heading = 0.;
+    let mut corners = Vec::with_capacity(lens.len());
+    for (i, l) in lens.iter().enumerate() {
+        let (sin, cos) = f64::sin_cos(heading);
+        corner = (corner.0 + l * cos, corner.1 + l * sin);
+        corners.push(corner);
+        if let Some(t) = turns.get(i) {
+            heading += *t as f64 * FRAC_PI_2;
+        }
+    }
+    Some((corners, turns))
+}
+
+//turning angle, leg length and centerline reach to the side of a bump gaining `extra`, turning `a` out and back gains
+//`4 * radius * (a - sin(a))` over `4 * radius * sin(a)` forward
+fn bump(extra: f64, radius: f64) -> (f64, f64, f64) {
+    let gain = |a: f64| radius * 4. * (a - a.sin());
+    if extra >= gain(FRAC_PI_2) {
+        let leg = (extra - gain(FRAC_PI_2)) / 2.;
+        return (FRAC_PI_2, leg, radius * 2. + leg);
+    }
+    let (mut lo, mut hi) = (0., FRAC_PI_2);
+    for _ in 0..64 {
+        let mid = (lo + hi) / 2.;
+        if gain(mid) < extra {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    let a = (lo + hi) / 2.;
+    (a, 0., radius * 2. * (1. - a.cos()))
+}
+
+//target position in the frame of the cursor and the quarter turns between the cursor and the target direction
+fn local_target<L, T>(
+    cursor: &Cursor<L, T>,
+    pos: LenCo<L, T>,
+    dir: Angle<T>,
+) -> Result<((f64, f64), i32), RouteError>
+where
+    L: LengthType,
+    T: Num + FloatConst + FromPrimitive,
+{
+    let quarters = radians(dir - cursor.dir) / FRAC_PI_2;
+    if (quarters - quarters.round()).abs() > TOLERANCE {
+        return Err(RouteError::NotManhattan);
+    }
+    Ok((
+        to_local(cursor, pos),
+        (quarters.round() as i32).rem_euclid(4),
+    ))
+}
+
+fn to_local<L, T>(cursor: &Cursor<L, T>, pos: LenCo<L, T>) -> (f64, f64)
+where
+    L: LengthType,
+    T: Num + FloatConst + FromPrimitive,
+{
+    let (sin, cos) = radians(cursor.dir).sin_cos();
+    let d = pos - cursor.pos;
+    let (x, y) = (value(d[0]), value(d[1]));
+    (x * cos + y * sin, y * cos - x * sin)
+}
+
+fn to_world<L, T>(cursor: &Cursor<L, T>, (x, y): (f64, f64)) -> LenCo<L, T>
+where
+    L: LengthType,
+    T: Num + FloatConst + FromPrimitive,
+{
+    let (sin, cos) = radians(cursor.dir).sin_cos();
+    cursor.pos + Coordinate::from([length(x * cos - y * sin), length(x * sin + y * cos)])
+}
+
+//nodes of the routing grid in the frame of the cursor, `blocked` is indexed by `iy * xs.len() + ix`
+struct Grid {
+    xs: Vec<f64>,
+    ys: Vec<f64>,
+    blocked: Vec<bool>,
+}
+
+//multiples of `step` covering the range and the extra coordinate
+fn grid_axis((lo, hi): (f64, f64), step: f64, extra: f64) -> Vec<f64> {
+    let mut axis: Vec<f64> = ((lo / step).floor() as i64..=(hi / step).ceil() as i64)
+        .map(|i| i as f64 * step)
+        .collect();
+    if axis.iter().all(|a| (a - extra).abs() > TOLERANCE) {
+        axis.push(extra);
+        axis.sort_by(f64::total_cmp);
+    }
+    axis
+}
+
+//indices of the coordinates in `[lo, hi]`
+fn span(axis: &[f64], lo: f64, hi: f64) -> Range<usize> {
+    axis.partition_point(|a| *a < lo)..axis.partition_point(|a| *a <= hi)
+}
+
+fn nearest(axis: &[f64], v: f64) -> Option<usize> {
+    axis.iter().position(|a| (a - v).abs() <= TOLERANCE)
+}
+
+//lower left and upper right corners
+fn bounds(points: &[(f64, f64)]) -> ((f64, f64), (f64, f64)) {
+    points.iter().fold(
+        (
+            (f64::INFINITY, f64::INFINITY),
+            (f64::NEG_INFINITY, f64::NEG_INFINITY),
+        ),
+        |(lo, hi), p| {
+            (
+                (lo.0.min(p.0), lo.1.min(p.1)),
+                (hi.0.max(p.0), hi.1.max(p.1)),
+            )
+        },
+    )
+}
+
+fn segment_distance(p: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let len2 = dx * dx + dy * dy;
+    let t = if len2 > 0. {
+        (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / len2).clamp(0., 1.)
+    } else {
+        0.
+    };
+    (p.0 - a.0 - t * dx).hypot(p.1 - a.1 - t * dy)
+}
+
+//even-odd rule, the outline is closed
+fn inside(outline: &[(f64, f64)], p: (f64, f64)) -> bool {
+    outline
+        .windows(2)
+        .filter(|w| (w[0].1 <= p.1) != (w[1].1 <= p.1))
+        .filter(|w| p.0 < w[0].0 + (p.1 - w[0].1) * (w[1].0 - w[0].0) / (w[1].1 - w[0].1))
+        .count()
+        % 2
+        == 1
+}
+
+//the point is on the shape, which is the outline grown by `extra` and filled if it is closed
+fn covers(outline: &[(f64, f64)], extra: f64, closed: bool, p: (f64, f64)) -> bool {
+    outline
+        .windows(2)
+        .any(|w| segment_distance(p, w[0], w[1]) <= extra + TOLERANCE)
+        || (closed && inside(outline, p))
+}
+
+impl Grid {
+    fn index(&self, (ix, iy): (usize, usize)) -> usize {
+        iy * self.xs.len() + ix
+    }
+    fn is_blocked(&self, node: (usize, usize)) -> bool {
+        self.blocked[self.index(node)]
+    }
+    //the next node towards the heading, 0 is +x and every step is a quarter turn to the left
+    fn neighbour(&self, (ix, iy): (usize, usize), heading: u8) -> Option<(usize, usize)> {
+        let node = match heading {
+            0 => (ix + 1, iy),
+            1 => (ix, iy + 1),
+            2 => (ix.checked_sub(1)?, iy),
+            _ => (ix, iy.checked_sub(1)?),
+        };
+        (node.0 < self.xs.len() && node.1 < self.ys.len()).then_some(node)
+    }
+    fn pos(&self, (ix, iy): (usize, usize)) -> (f64, f64) {
+        (self.xs[ix], self.ys[iy])
+    }
+    //nodes closer than `clearance` to the outline, and inside it if it is closed
+    fn block(&mut self, outline: &[(f64, f64)], clearance: f64, closed: bool) {
+        for w in outline.windows(2) {
+            let (a, b) = (w[0], w[1]);
+            for iy in span(&self.ys, a.1.min(b.1) - clearance, a.1.max(b.1) + clearance) {
+                for ix in span(&self.xs, a.0.min(b.0) - clearance, a.0.max(b.0) + clearance) {
+                    if segment_distance(self.pos((ix, iy)), a, b) < clearance {
+                        let i = self.index((ix, iy));
+                        self.blocked[i] = true;
+                    }
+                }
+            }
+        }
+        if !closed {
+            return;
+        }
+        //even-odd fill along every row
+        let (lo, hi) = bounds(outline);
+        for iy in span(&self.ys, lo.1, hi.1) {
+            let y = self.ys[iy];
+            let mut crossings: Vec<f64> = outline
+                .windows(2)
+                .filter(|w| (w[0].1 <= y) != (w[1].1 <= y))
+                .map(|w| w[0].0 + (y - w[0].1) * (w[1].0 - w[0].0) / (w[1].1 - w[0].1))
+                .collect();
+            crossings.sort_by(f64::total_cmp);
+            for pair in crossings.chunks_exact(2) {
+                for ix in span(&self.xs, pair[0], pair[1]) {
+                    let i = self.index((ix, iy));
+                    self.blocked[i] = true;
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct State {
+    node: (usize, usize),
+    heading: u8,
+    //length since the last turn in grid steps, saturated once the next turn is allowed
+    progress: u8,
+}
+
+//the cheapest first, then the longest since the last turn
+struct Visit {
+    cost: f64,
+    since: f64,
+    state: State,
+}
+
+impl PartialEq for Visit {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Visit {}
+
+impl PartialOrd for Visit {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Visit {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .cost
+            .total_cmp(&self.cost)
+            .then(self.since.total_cmp(&other.since))
+    }
+}
+
+//dijkstra over the grid nodes and headings, turns are allowed once the last one is `2 * radius` behind and cost an
+//extra `radius`, returns the corners and the goal
+fn search(
+    grid: &Grid,
+    start: (usize, usize),
+    goal: (usize, usize),
+    goal_heading: u8,
+    radius: f64,
+    step: f64,
+) -> Option<Vec<(usize, usize)>> {
+    let full = (radius * 2. / step).ceil();
+    let progress = |since: f64| ((since + TOLERANCE) / step).floor().min(full) as u8;
+    //the start is no corner, so the first turn only needs `radius`
+    let first = State {
+        node: start,
+        heading: 0,
+        progress: progress(radius),
+    };
+    let mut best: HashMap<State, (f64, f64, Option<State>)> = HashMap::new();
+    let mut heap = BinaryHeap::new();
+    best.insert(first, (0., radius, None));
+    heap.push(Visit {
+        cost: 0.,
+        since: radius,
+        state: first,
+    });
+    while let Some(Visit { cost, since, state }) = heap.pop() {
+        let (c, s, _) = best[&state];
+        if (cost, since) != (c, s) {
+            continue;
+        }
+        if state.node == goal && state.heading == goal_heading && since >= radius - TOLERANCE {
+            let mut states = vec![state];
+            while let Some(prev) = best[states.last().unwrap()].2 {
+                states.push(prev);
+            }
+            let mut corners: Vec<_> = states
+                .windows(2)
+                .rev()
+                .filter(|w| w[0].heading != w[1].heading)
+                .map(|w| w[0].node)
+                .collect();
+            corners.push(goal);
+            return Some(corners);
+        }
+        let mut next = Vec::with_capacity(3);
+        if let Some(node) = grid.neighbour(state.node, state.heading) {
+            if !grid.is_blocked(node) {
+                let (a, b) = (grid.pos(state.node), grid.pos(node));
+                let len = (b.0 - a.0).abs() + (b.1 - a.1).abs();
+                next.push((
+                    cost + len,
+                    since + len,
+                    State {
+                        node,
+                        progress: progress(since + len),
+                        ..state
+                    },
+                ));
+            }
+        }
+        if since >= radius * 2. - TOLERANCE {
+            for turn in [1, 3] {
+                next.push((
+                    cost + radius,
+                    0.,
+                    State {
+                        heading: (state.heading + turn) % 4,
+                        progress: 0,
+                        ..state
+                    },
+                ));
+            }
+        }
+        for (cost, since, s) in next {
+            let better = match best.get(&s) {
+                Some((c, old, _)) => cost < c - TOLERANCE || (cost < c + TOLERANCE && since > *old),
+                None => true,
+            };
+            if better {
+                best.insert(s, (cost, since, Some(state)));
+                heap.push(Visit {
+                    cost,
+                    since,
+                    state: s,
+                });
+            }
+        }
+    }
+    None
+}
+
+//farthest edge from the centerline
+fn reach<L: LengthType, T: Num, W: Profile<Length<L, T>>>(width: &W) -> f64 {
+    width.edges().iter().fold(0., |m: f64, (r, l)| {
+        m.max(value(*r).abs()).max(value(*l).abs())
+    })
+}
+
+fn take_points<L: LengthType, T: Num>(
+    points: &mut Box<dyn Iterator<Item = LenCo<L, T>>>,
+) -> Vec<LenCo<L, T>> {
+    let v: Vec<_> = mem::replace(points, Box::new(std::iter::empty())).collect();
+    *points = Box::new(v.clone().into_iter());
+    v
+}
+
+impl<Cell, W, L, T> Assembler<Cell, W, L, T>
+where
+    Cell: AsMut<DgirCell<Length<L, T>>>,
+    W: 'static + Clone + Profile<Length<L, T>>,
+    L: LengthType,
+    T: Num + Float + FloatConst + FromPrimitive + RealField,
+{
+    //route of straights and quarter turns ending exactly at the target, up to four turns are tried
+    pub fn route_to<P: Into<LenCo<L, T>>>(
+        &mut self,
+        target_pos: P,
+        target_dir: Angle<T>,
+    ) -> Result<&mut Self, RouteError> {
+        let radius = value(self.radius.ok_or(RouteError::NoRadius)?);
+        let cursor = self.cell_cur.cursor;
+        let target_pos = target_pos.into();
+        let (local, q) = local_target(&cursor, target_pos, target_dir)?;
+        let (corners, _) = manhattan(local, q, radius).ok_or(RouteError::Unreachable)?;
+        let mut corners: Vec<_> = corners.into_iter().map(|c| to_world(&cursor, c)).collect();
+        *corners.last_mut().unwrap() = target_pos;
+        self.route_through(&corners)?;
+        self.cell_cur.cursor.pos = target_pos;
+        self.cell_cur.cursor.dir = target_dir;
+        Ok(self)
+    }
+    //manhattan route to the target keeping `spacing` from the polygons and paths of the cell on `layers`, searched
+    //on a grid of half the bend radius, references are kept clear by their bounding boxes whatever their layers, the
+    //structures under the cursor or the target are only kept out of
+    pub fn route_around<P: Into<LenCo<L, T>>>(
+        &mut self,
+        target_pos: P,
+        target_dir: Angle<T>,
+        layers: &[LayerData],
+        spacing: Length<L, T>,
+    ) -> Result<&mut Self, RouteError> {
+        let radius = value(self.radius.ok_or(RouteError::NoRadius)?);
+        let cursor = self.cell_cur.cursor;
+        let target_pos = target_pos.into();
+        let (target, q) = local_target(&cursor, target_pos, target_dir)?;
+        let step = radius / 2.;
+        let half_width = reach(&self.width);
+        //a bend cuts its corner by up to `(1 - 1/sqrt(2)) * radius` and a grid edge is up to a step long
+        let clearance = value(spacing) + half_width + radius * (1. - FRAC_1_SQRT_2) + step / 2.;
+        self.flush();
+        let mut shapes = Vec::new();
+        for e in self.mut_cell().elements.iter_mut() {
+            match e {
+                Element::Polygon(p) if layers.contains(&p.color) => {
+                    shapes.push((take_points(&mut p.area), 0., true))
+                }
+                Element::Path(p) if layers.contains(&p.color) => shapes.push((
+                    take_points(&mut p.curve),
+                    p.width.map_or(0., |w| value(w).abs() / 2.),
+                    false,
+                )),
+                //the cells behind references can't be read
+                Element::Ref(r) => {
+                    shapes.extend(r.extent_corners().map(|c| (c.to_vec(), 0., true)))
+                }
+                Element::ARef(ar) => shapes.extend(
+                    ar.extent_corners()
+                        .into_iter()
+                        .map(|c| (c.to_vec(), 0., true)),
+                ),
+                _ => (),
+            }
+        }
+        let mut outlines = Vec::new();
+        for (points, extra, closed) in shapes {
+            let mut outline: Vec<_> = points.into_iter().map(|p| to_local(&cursor, p)).collect();
+            if outline.is_empty() {
+                continue;
+            }
+            if closed && outline.first() != outline.last() {
+                outline.push(outline[0]);
+            }
+            let on_end = covers(&outline, extra, closed, (0., 0.))
+                || covers(&outline, extra, closed, target);
+            let reach = if on_end { extra } else { clearance + extra };
+            outlines.push((outline, reach, closed));
+        }
+        let margin = clearance + radius * 2. + step;
+        let (mut lo, mut hi) = bounds(&[(0., 0.), target]);
+        for (outline, reach, _) in outlines.iter() {
+            let (l, h) = bounds(outline);
+            lo = (lo.0.min(l.0 - reach), lo.1.min(l.1 - reach));
+            hi = (hi.0.max(h.0 + reach), hi.1.max(h.1 + reach));
+        }
+        let xs = grid_axis((lo.0 - margin, hi.0 + margin), step, target.0);
+        let ys = grid_axis((lo.1 - margin, hi.1 + margin), step, target.1);
+        let mut grid = Grid {
+            blocked: vec![false; xs.len() * ys.len()],
+            xs,
+            ys,
+        };
+        for (outline, reach, closed) in outlines.iter() {
+            grid.block(outline, *reach, *closed);
+        }
+        let node = |p: (f64, f64)| Some((nearest(&grid.xs, p.0)?, nearest(&grid.ys, p.1)?));
+        let start = node((0., 0.)).ok_or(RouteError::NoRoute)?;
+        let goal = node(target).ok_or(RouteError::NoRoute)?;
+        //the ends sit on the structures they connect
+        for n in [start, goal] {
+            let i = grid.index(n);
+            grid.blocked[i] = false;
+        }
+        let corners =
+            search(&grid, start, goal, q as u8, radius, step).ok_or(RouteError::NoRoute)?;
+        let mut corners: Vec<_> = corners
+            .into_iter()
+            .map(|n| to_world(&cursor, grid.pos(n)))
+            .collect();
+        *corners.last_mut().unwrap() = target_pos;
+        self.route_through(&corners)?;
+        self.cell_cur.cursor.pos = target_pos;
+        self.cell_cur.cursor.dir = target_dir;
+        Ok(self)
+    }
+    //routes `starts[i]` to `ends[i]` together with `pitch` between the neighbours, s-bends join the starts to the
+    //pitch and leave it for the ends, the bends are concentric and the innermost one has the bend radius, the cursor is
+    //left at the last end
+    pub fn route_bundle(
+        &mut self,
+        starts: &[Cursor<L, T>],
+        ends: &[Cursor<L, T>],
+        pitch: Length<L, T>,
+    ) -> Result<&mut Self, RouteError> {
+        let radius = value(self.radius.ok_or(RouteError::NoRadius)?);
+        if starts.len() != ends.len() {
+            return Err(RouteError::Unpaired);
+        }
+        let (start_frame, end_frame) = match (starts.first(), ends.first()) {
+            (Some(s), Some(e)) => (*s, *e),
+            _ => return Ok(self),
+        };
+        let parallel = |frame: &Cursor<L, T>, c: &Cursor<L, T>| {
+            normalize(radians(c.dir - frame.dir)).abs() <= TOLERANCE
+        };
+        if !starts.iter().all(|c| parallel(&start_frame, c))
+            || !ends.iter().all(|c| parallel(&end_frame, c))
+        {
+            return Err(RouteError::NotParallel);
+        }
+        let width = reach(&self.width) * 2.;
+        let pitch = value(pitch);
+        if pitch <= width {
+            warn!(
+                "the bundle pitch {} is not larger than the width {}",
+                pitch, width
+            );
+        }
+        let n = starts.len();
+        let s: Vec<_> = starts
+            .iter()
+            .map(|c| to_local(&start_frame, c.pos))
+            .collect();
+        let e: Vec<_> = ends.iter().map(|c| to_local(&end_frame, c.pos)).collect();
+        //from right to left at the starts, which has to be the same at the ends
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|a, b| s[*a].1.total_cmp(&s[*b].1));
+        if order
+            .windows(2)
+            .any(|w| s[w[1]].1 - s[w[0]].1 <= TOLERANCE || e[w[1]].1 - e[w[0]].1 <= TOLERANCE)
+        {
+            return Err(RouteError::Crossing);
+        }
+        //to the left of the center line of the bundle
+        let mut offsets = vec![0.; n];
+        for (rank, i) in order.iter().enumerate() {
+            offsets[*i] = (rank as f64 - (n - 1) as f64 / 2.) * pitch;
+        }
+        let center = |v: &[(f64, f64)]| v.iter().map(|p| p.1).sum::<f64>() / n as f64;
+        let (c0, c1) = (center(&s), center(&e));
+        //cosine s-bends whose smallest radius is the bend radius
+        let fan = |shift: f64| PI * (shift.abs() * radius / 2.).sqrt();
+        let fan_in = (0..n)
+            .map(|i| fan(c0 + offsets[i] - s[i].1))
+            .fold(0., f64::max);
+        let fan_out = (0..n)
+            .map(|i| fan(e[i].1 - c1 - offsets[i]))
+            .fold(0., f64::max);
+        let front = s.iter().fold(f64::NEG_INFINITY, |m, p| m.max(p.0));
+        let back = e.iter().fold(f64::INFINITY, |m, p| m.min(p.0));
+        let spine_radius = radius + (n - 1) as f64 / 2. * pitch;
+        let spine = Cursor::new(
+            to_world(&start_frame, (front + fan_in, c0)),
+            start_frame.dir,
+        );
+        let (target, q) = local_target(
+            &spine,
+            to_world(&end_frame, (back - fan_out, c1)),
+            end_frame.dir,
+        )?;
+        let (corners, turns) = manhattan(target, q, spine_radius).ok_or(RouteError::Unreachable)?;
+        let headings: Vec<i32> = once(0)
+            .chain(turns.iter().scan(0, |h, t| {
+                *h += t;
+                Some(*h)
+            }))
+            .collect();
+        let normal = |h: i32| {
+            let (sin, cos) = (h as f64 * FRAC_PI_2).sin_cos();
+            (-sin, cos)
+        };
+        for i in 0..n {
+            let o = offsets[i];
+            //the offset lines of the two segments at a corner meet at `corner + o * (n1 + n2)`
+            let shifted: Vec<_> = corners
+                .iter()
+                .enumerate()
+                .map(|(j, c)| {
+                    let a = normal(headings[j]);
+                    let k = match headings.get(j + 1) {
+                        Some(h) => (a.0 + normal(*h).0, a.1 + normal(*h).1),
+                        None => a,
+                    };
+                    to_world(&spine, (c.0 + o * k.0, c.1 + o * k.1))
+                })
+                .collect();
+            self.set_pos(starts[i].pos).set_dir(start_frame.dir);
+            let lead = front - s[i].0;
+            if lead > TOLERANCE {
+                self.extend(length(lead));
+            }
+            self.fan(fan_in, c0 + o - s[i].1);
+            self.follow(&shifted, spine_radius, o)?;
+            self.fan(fan_out, e[i].1 - c1 - o);
+            let tail = e[i].0 - back;
+            if tail > TOLERANCE {
+                self.extend(length(tail));
+            }
+            self.cell_cur.cursor.pos = ends[i].pos;
+            self.cell_cur.cursor.dir = end_frame.dir;
+        }
+        Ok(self)
+    }
+    //`shift` is to the left in the world, which a mirrored cursor draws to the right of its own
+    fn fan(&mut self, len: f64, shift: f64) {
+        let hand = if self.cell_cur.mirrored() { -1. } else { 1. };
+        if len > TOLERANCE {
+            if shift.abs() > TOLERANCE {
+                self.sbend(length(len), length(shift * hand));
+            } else {
+                self.extend(length(len));
+            }
+        }
+    }
+    //goes `len` forward drawing `extra` more centerline, with bumps of three arcs turning out and back, which get
+    //straight legs once they are half circles, as many bumps as fit in `len` alternate sides
+    pub fn meander(
+        &mut self,
+        len: Length<L, T>,
+        extra: Length<L, T>,
+    ) -> Result<&mut Self, RouteError> {
+        let radius = value(self.radius.ok_or(RouteError::NoRadius)?);
+        let k = ((value(len) / (radius * 4.)).floor() as usize).max(1);
+        self.bumps(value(len), value(extra), radius, k)
+    }
+    //serpentine delay line going `region.0` forward and drawing `extra` more centerline, which stays within
+    //`region.1 / 2` on both sides of the cursor, the fewest and so the longest lanes that fit are taken
+    pub fn delay_line(
+        &mut self,
+        extra: Length<L, T>,
+        region: (Length<L, T>, Length<L, T>),
+    ) -> Result<&mut Self, RouteError> {
+        let radius = value(self.radius.ok_or(RouteError::NoRadius)?);
+        let half_width = reach(&self.width);
+        let (len, extra, side) = (value(region.0), value(extra), value(region.1) / 2.);
+        let k = (1..=(len / (radius * 4.)).floor() as usize)
+            .find(|k| bump(extra / *k as f64, radius).2 + half_width <= side + TOLERANCE)
+            .ok_or(RouteError::NoRoom)?;
+        self.bumps(len, extra, radius, k)
+    }
+    fn bumps(
+        &mut self,
+        len: f64,
+        extra: f64,
+        radius: f64,
+        k: usize,
+    ) -> Result<&mut Self, RouteError> {
+        if extra < -TOLERANCE {
+            return Err(RouteError::TooLong);
+        }
+        if extra <= TOLERANCE {
+            if len > TOLERANCE {
+                self.extend(length(len));
+            }
+            return Ok(self);
+        }
+        let (a, leg, _) = bump(extra / k as f64, radius);
+        let lead = (len - k as f64 * radius * 4. * a.sin()) / 2.;
+        if lead < -TOLERANCE {
+            return Err(RouteError::TooShort);
+        }
+        if lead > TOLERANCE {
+            self.extend(length(lead));
+        }
+        for j in 0..k {
+            let a = if j % 2 == 0 { a } else { -a };
+            self.turn(length(radius), angle(a));
+            if leg > TOLERANCE {
+                self.extend(length(leg));
+            }
+            self.turn(length(radius), angle(-a * 2.));
+            if leg > TOLERANCE {
+                self.extend(length(leg));
+            }
+            self.turn(length(radius), angle(a));
+        }
+        if lead > TOLERANCE {
+            self.extend(length(lead));
+        }
+        Ok(self)
+    }
+    //goes `len` forward with a meander making the path length since the last reset `total`
+    pub fn extend_to_length(
+        &mut self,
+        len: Length<L, T>,
+        total: Length<L, T>,
+    ) -> Result<&mut Self, RouteError> {
+        let extra = total - self.path_length().total() - len;
+        self.meander(len, extra)
+    }
+    //follows the polyline from the cursor through `points`, every corner is rounded with the bend radius, nothing is
+    //drawn if the polyline does not fit
+    pub fn route_through(&mut self, points: &[LenCo<L, T>]) -> Result<&mut Self, RouteError> {
+        let radius = value(self.radius.ok_or(RouteError::NoRadius)?);
+        self.follow(points, radius, 0.)
+    }
+    //bends are concentric with the ones of a center line `offset` to the right, whose bend radius is `radius`
+    fn follow(
+        &mut self,
+        points: &[LenCo<L, T>],
+        radius: f64,
+        offset: f64,
+    ) -> Result<&mut Self, RouteError> {
+        let cursor = self.cell_cur.cursor;
+        //direction and length of every segment
+        let segments: Vec<(f64, f64)> = once(&cursor.pos)
+            .chain(points.iter())
+            .zip(points.iter())
+            .map(|(a, b)| {
+                let d = *b - *a;
+                let (x, y) = (value(d[0]), value(d[1]));
+                (y.atan2(x), x.hypot(y))
+            })
+            .filter(|(_, l)| *l > TOLERANCE)
+            .collect();
+        let first = match segments.first() {
+            Some(s) => s.0,
+            None => return Ok(self),
+        };
+        //turning at the start of every segment
+        let angles: Vec<f64> = once(first - radians(cursor.dir))
+            .chain(segments.windows(2).map(|w| w[1].0 - w[0].0))
+            .map(normalize)
+            .collect();
+        if angles[0].abs() > TOLERANCE {
+            return Err(RouteError::Misaligned);
+        }
+        let radii: Vec<f64> = angles
+            .iter()
+            .map(|a| {
+                if a.abs() > TOLERANCE {
+                    radius - offset * a.signum()
+                } else {
+                    radius
+                }
+            })
+            .collect();
+        if radii.iter().any(|r| *r <= 0.) {
+            return Err(RouteError::TooShort);
+        }
+        let tangents: Vec<f64> = angles
+            .iter()
+            .zip(radii.iter())
+            .map(|(a, r)| r * (a.abs() / 2.).tan())
+            .collect();
+        let straights: Vec<f64> = segments
+            .iter()
+            .enumerate()
+            .map(|(i, (_, l))| l - tangents[i] - tangents.get(i + 1).unwrap_or(&0.))
+            .collect();
+        if straights.iter().any(|s| *s < -TOLERANCE) {
+            return Err(RouteError::TooShort);
+        }
+        //the turns are drawn reflected by a mirrored cursor
+        let hand = if self.cell_cur.mirrored() { -1. } else { 1. };
+        for (i, s) in straights.into_iter().enumerate() {
+            if i > 0 {
+                self.turn(length(radii[i]), angle(angles[i] * hand));
+            }
+            if s > TOLERANCE {
+                self.extend(length(s));
+            }
+        }
+        self.cell_cur.cursor.pos = *points.last().unwrap();
+        self.cell_cur.cursor.dir = angle(segments.last().unwrap().0);
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        color::Colour,
+        draw::{
+            bbox::{Bounded, BoundingBox},
+            curve::Area,
+            Resolution, APPROX_EQ_MARGIN,
+        },
+        units::Absolute,
+        zero, MICROMETER, NANOMETER,
+    };
+    use float_cmp::ApproxEq;
+
+    fn assembler() -> Assembler<DgirCell, [Length<Absolute, f64>; 1]> {
+        let mut a = Assembler::new(
+            "route",
+            [LayerData::new(1, 0)],
+            [MICROMETER * 2.],
+            Resolution::MinDistance(NANOMETER * 20.),
+        );
+        a.set_radius(MICROMETER * 10.);
+        a
+    }
+
+    #[test]
+    fn manhattan_route() {
+        let mut a = assembler();
+        a.route_to([MICROMETER * 100., MICROMETER * 50.], Angle::from_deg(0.))
+            .unwrap();
+        assert!(a.cell_cur.cursor.pos.approx_eq(
+            [MICROMETER * 100., MICROMETER * 50.].into(),
+            APPROX_EQ_MARGIN
+        ));
+        //straight, left turn, straight and right turn ending right at the target
+        assert_eq!(a.mut_cell().elements.len(), 4);
+        let b = a.bounding_box().unwrap();
+        assert!(b.max()[0].approx_eq(MICROMETER * 100., APPROX_EQ_MARGIN));
+        assert!(b.min()[1].approx_eq(-MICROMETER, APPROX_EQ_MARGIN));
+
+        //too close for an s-route, so it loops around
+        let mut a = assembler();
+        a.set_pos([zero(), zero()]).set_dir(Angle::from_deg(90.));
+        a.route_to([MICROMETER * 5., MICROMETER * 5.], Angle::from_deg(90.))
+            .unwrap();
+        assert!(a
+            .cell_cur
+            .cursor
+            .pos
+            .approx_eq([MICROMETER * 5., MICROMETER * 5.].into(), APPROX_EQ_MARGIN));
+        assert_eq!(a.cell_cur.cursor.dir.to_deg(), 90.);
+
+        let mut a = assembler();
+        a.route_to([-MICROMETER * 30., MICROMETER * 40.], Angle::from_deg(180.))
+            .unwrap();
+        assert!(a.bounding_box().unwrap().max()[0].approx_eq(MICROMETER * 11., APPROX_EQ_MARGIN));
+        assert_eq!(
+            a.route_to([zero(), zero()], Angle::from_deg(45.)).err(),
+            Some(RouteError::NotManhattan)
+        );
+        a.radius = None;
+        assert_eq!(
+            a.route_to([zero(), zero()], Angle::from_deg(0.)).err(),
+            Some(RouteError::NoRadius)
+        );
+    }
+
+    #[test]
+    fn route_polyline() {
+        let mut a = assembler();
+        assert_eq!(
+            a.route_through(&[[zero(), MICROMETER * 10.].into()]).err(),
+            Some(RouteError::Misaligned)
+        );
+        assert_eq!(
+            a.route_through(&[
+                [MICROMETER * 5., zero()].into(),
+                [MICROMETER * 5., MICROMETER * 20.].into()
+            ])
+            .err(),
+            Some(RouteError::TooShort)
+        );
+        assert!(a.mut_cell().elements.is_empty());
+        a.route_through(&[
+            [MICROMETER * 20., zero()].into(),
+            [MICROMETER * 40., MICROMETER * 20.].into(),
+        ])
+        .unwrap();
+        assert!(a
+            .cell_cur
+            .cursor
+            .dir
+            .approx_eq(Angle::from_deg(45.), APPROX_EQ_MARGIN));
+        assert_eq!(a.mut_cell().elements.len(), 3);
+    }
+
+    #[test]
+    fn avoid_obstacles() {
+        let obstacle = LayerData::new(2, 0);
+        let mut a = assembler();
+        a.mut_cell().push(obstacle.color(Area {
+            area: vec![
+                Coordinate::from([MICROMETER * 80., -MICROMETER * 20.]),
+                Coordinate::from([MICROMETER * 120., -MICROMETER * 20.]),
+                Coordinate::from([MICROMETER * 120., MICROMETER * 20.]),
+                Coordinate::from([MICROMETER * 80., MICROMETER * 20.]),
+            ],
+        }));
+        a.route_around(
+            [MICROMETER * 200., zero()],
+            Angle::from_deg(0.),
+            &[obstacle],
+            MICROMETER * 5.,
+        )
+        .unwrap();
+        assert!(a
+            .cell_cur
+            .cursor
+            .pos
+            .approx_eq([MICROMETER * 200., zero()].into(), APPROX_EQ_MARGIN));
+        let keep_out = BoundingBox::new(
+            [MICROMETER * 80., -MICROMETER * 20.],
+            [MICROMETER * 120., MICROMETER * 20.],
+        )
+        .expand(MICROMETER * 5.);
+        let elements = &mut a.mut_cell().elements;
+        assert!(elements.len() > 2);
+        for e in elements.iter_mut().skip(1) {
+            if let Element::Polygon(p) = e {
+                assert!(take_points(&mut p.area)
+                    .iter()
+                    .all(|p| !keep_out.contains(p)));
+            }
+        }
+
+        //obstacles on other layers are crossed
+        let mut a = assembler();
+        a.mut_cell().push(obstacle.color(Area {
+            area: vec![
+                Coordinate::from([MICROMETER * 80., -MICROMETER * 20.]),
+                Coordinate::from([MICROMETER * 120., -MICROMETER * 20.]),
+                Coordinate::from([MICROMETER * 120., MICROMETER * 20.]),
+                Coordinate::from([MICROMETER * 80., MICROMETER * 20.]),
+            ],
+        }));
+        a.route_around(
+            [MICROMETER * 200., zero()],
+            Angle::from_deg(0.),
+            &[LayerData::new(3, 0)],
+            MICROMETER * 5.,
+        )
+        .unwrap();
+        assert_eq!(a.mut_cell().elements.len(), 2);
+    }
+
+    #[test]
+    fn obstacle_around_start() {
+        let obstacle = LayerData::new(2, 0);
+        let mut a = assembler();
+        //an L whose bounding box holds the start
+        let l: Vec<Coordinate<_>> = [
+            (-20., 30.),
+            (120., 30.),
+            (120., -20.),
+            (80., -20.),
+            (80., 20.),
+            (-20., 20.),
+        ]
+        .into_iter()
+        .map(|(x, y)| [MICROMETER * x, MICROMETER * y].into())
+        .collect();
+        a.mut_cell().push(obstacle.color(Area { area: l }));
+        a.route_around(
+            [MICROMETER * 200., zero()],
+            Angle::from_deg(0.),
+            &[obstacle],
+            MICROMETER * 5.,
+        )
+        .unwrap();
+        let keep_out = [
+            BoundingBox::new(
+                [MICROMETER * 80., -MICROMETER * 20.],
+                [MICROMETER * 120., MICROMETER * 30.],
+            ),
+            BoundingBox::new(
+                [-MICROMETER * 20., MICROMETER * 20.],
+                [MICROMETER * 120., MICROMETER * 30.],
+            ),
+        ]
+        .map(|b| b.expand(MICROMETER * 5.));
+        let elements = &mut a.mut_cell().elements;
+        assert!(elements.len() > 2);
+        for e in elements.iter_mut().skip(1) {
+            if let Element::Polygon(p) = e {
+                assert!(take_points(&mut p.area)
+                    .iter()
+                    .all(|p| keep_out.iter().all(|b| !b.contains(p))));
+            }
+        }
+    }
+
+    #[test]
+    fn avoid_references() {
+        let mut component = DgirCell::new("component");
+        component.push(LayerData::new(2, 0).color(Area {
+            area: vec![
+                Coordinate::from([-MICROMETER * 30., -MICROMETER * 10.]),
+                Coordinate::from([MICROMETER * 30., -MICROMETER * 10.]),
+                Coordinate::from([MICROMETER * 30., MICROMETER * 10.]),
+                Coordinate::from([-MICROMETER * 30., MICROMETER * 10.]),
+            ],
+        }));
+        let mut r = component.into_ref_at([MICROMETER * 100., zero()]);
+        r.set_rot(Angle::from_deg(90.));
+        let mut a = assembler();
+        a.mut_cell().push(r);
+        //references are obstacles whatever the layers
+        a.route_around(
+            [MICROMETER * 200., zero()],
+            Angle::from_deg(0.),
+            &[],
+            MICROMETER * 5.,
+        )
+        .unwrap();
+        assert!(a
+            .cell_cur
+            .cursor
+            .pos
+            .approx_eq([MICROMETER * 200., zero()].into(), APPROX_EQ_MARGIN));
+        let keep_out = BoundingBox::new(
+            [MICROMETER * 90., -MICROMETER * 30.],
+            [MICROMETER * 110., MICROMETER * 30.],
+        )
+        .expand(MICROMETER * 5.);
+        let elements = &mut a.mut_cell().elements;
+        assert!(elements.len() > 3);
+        for e in elements.iter_mut().skip(2) {
+            if let Element::Polygon(p) = e {
+                assert!(take_points(&mut p.area)
+                    .iter()
+                    .all(|p| !keep_out.contains(p)));
+            }
+        }
+    }
+
+    #[test]
+    fn bundle_route() {
+        let starts: Vec<Cursor> = (0..4)
+            .map(|i| Cursor::new([zero(), MICROMETER * 20. * i as f64], Angle::from_deg(0.)))
+            .collect();
+        let ends: Vec<Cursor> = (0..4)
+            .map(|i| {
+                Cursor::new(
+                    [MICROMETER * (330. - 10. * i as f64), MICROMETER * 400.],
+                    Angle::from_deg(90.),
+                )
+            })
+            .collect();
+        let mut a = assembler();
+        a.route_bundle(&starts, &ends, MICROMETER * 5.).unwrap();
+        assert!(a
+            .cell_cur
+            .cursor
+            .pos
+            .approx_eq(ends[3].pos, APPROX_EQ_MARGIN));
+        let b = a.bounding_box().unwrap();
+        assert!(b
+            .min()
+            .approx_eq([zero(), -MICROMETER].into(), APPROX_EQ_MARGIN));
+        assert!(b.max().approx_eq(
+            [MICROMETER * 331., MICROMETER * 400.].into(),
+            APPROX_EQ_MARGIN
+        ));
+
+        let mut reversed = ends.clone();
+        reversed.reverse();
+        assert_eq!(
+            a.route_bundle(&starts, &reversed, MICROMETER * 5.).err(),
+            Some(RouteError::Crossing)
+        );
+        assert_eq!(
+            a.route_bundle(&starts, &ends[1..], MICROMETER * 5.).err(),
+            Some(RouteError::Unpaired)
+        );
+    }
+
+    #[test]
+    fn mirrored_bundle_route() {
+        let starts: Vec<Cursor> = (0..4)
+            .map(|i| Cursor::new([zero(), MICROMETER * 20. * i as f64], Angle::from_deg(0.)))
+            .collect();
+        let ends: Vec<Cursor> = (0..4)
+            .map(|i| {
+                Cursor::new(
+                    [MICROMETER * (330. - 10. * i as f64), MICROMETER * 400.],
+                    Angle::from_deg(90.),
+                )
+            })
+            .collect();
+        let mut a = assembler();
+        a.route_bundle(&starts, &ends, MICROMETER * 5.).unwrap();
+        //the bundle is laid out in the world, a mirrored cursor draws the same waveguides
+        let mut m = assembler();
+        m.set_mirrored(true);
+        m.route_bundle(&starts, &ends, MICROMETER * 5.).unwrap();
+        assert!(m
+            .cell_cur
+            .cursor
+            .pos
+            .approx_eq(ends[3].pos, APPROX_EQ_MARGIN));
+        let (b, mb) = (a.bounding_box().unwrap(), m.bounding_box().unwrap());
+        assert!(mb.min().approx_eq(b.min(), APPROX_EQ_MARGIN));
+        assert!(mb.max().approx_eq(b.max(), APPROX_EQ_MARGIN));
+    }
+
+    #[test]
+    fn match_length() {
+        for (len, total) in [(100., 300.), (100., 130.), (45., 80.)] {
+            let mut a = assembler();
+            a.extend(MICROMETER * 10.)
+                .turn(MICROMETER * 10., Angle::from_deg(90.));
+            let start = a.cell_cur.cursor.pos;
+            a.extend_to_length(MICROMETER * len, MICROMETER * total)
+                .unwrap();
+            assert!(a
+                .path_length()
+                .total()
+                .approx_eq(MICROMETER * total, (1e-9, 1)));
+            assert!(a.cell_cur.cursor.pos.approx_eq(
+                start + Coordinate::from([zero(), MICROMETER * len]),
+                (1e-9, 1)
+            ));
+            assert!(a
+                .cell_cur
+                .cursor
+                .dir
+                .approx_eq(Angle::from_deg(90.), (1e-9, 1)));
+        }
+        let mut a = assembler();
+        a.extend(MICROMETER * 50.);
+        assert_eq!(
+            a.extend_to_length(MICROMETER * 10., MICROMETER * 40.).err(),
+            Some(RouteError::TooLong)
+        );
+        assert_eq!(
+            a.meander(MICROMETER * 10., MICROMETER * 30.).err(),
+            Some(RouteError::TooShort)
+        );
+    }
+
+    #[test]
+    fn serpentine() {
+        let mut a = assembler();
+        a.delay_line(MICROMETER * 1000., (MICROMETER * 200., MICROMETER * 250.))
+            .unwrap();
+        assert!(a
+            .path_length()
+            .total()
+            .approx_eq(MICROMETER * 1200., (1e-9, 1)));
+        assert!(a
+            .cell_cur
+            .cursor
+            .pos
+            .approx_eq([MICROMETER * 200., zero()].into(), (1e-9, 1)));
+        let b = a.bounding_box().unwrap();
+        assert!(b.height() <= MICROMETER * 250.);
+        //five bumps are the fewest that fit, so they are about as tall as the region
+        assert!(b.height() > MICROMETER * 200.);
+
+        let mut a = assembler();
+        assert_eq!(
+            a.delay_line(MICROMETER * 1000., (MICROMETER * 50., MICROMETER * 100.))
+                .err(),
+            Some(RouteError::NoRoom)
+        );
+    }
+
+    #[test]
+    fn relative_f32() {
+        use crate::units::{DbUnit, Relative};
+        let db = |v: f32| Length::<Relative, f32>::new_relative::<DbUnit>(v);
+        let mut a: Assembler<_, _, Relative, f32> = Assembler::new(
+            "db",
+            [LayerData::new(1, 0)],
+            [db(2000.)],
+            Resolution::MinNumber(65),
+        );
+        a.set_radius(db(10000.));
+        a.route_to([db(100000.), db(50000.)], Angle::from_deg(0.))
+            .unwrap();
+        assert!(a
+            .cell_cur
+            .cursor
+            .pos
+            .approx_eq([db(100000.), db(50000.)].into(), (1e-2, 4)));
+        let total = a.path_length().total() + db(80000.);
+        a.extend_to_length(db(60000.), total).unwrap();
+        assert!((a.path_length().total().value - total.value).abs() < 1.);
+        assert!(a
+            .cell_cur
+            .cursor
+            .pos
+            .approx_eq([db(160000.), db(50000.)].into(), (1e-2, 4)));
+    }
+}
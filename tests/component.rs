@@ -1,10 +1,12 @@
 use dgir::{
     color::{Decorated, LayerData},
     cursor::{ArcCurve, Assembler, CellCursor},
-    draw::{curve::groups::Group, CircularArc, Resolution},
+    draw::{bbox::Bounded, curve::groups::Group, CircularArc, Resolution},
+    gds::DgirCell,
     units::{AbsoluteLength, Angle},
-    zero, MICROMETER,
+    zero, MICROMETER, NANOMETER,
 };
+use float_cmp::ApproxEq;
 
 mod common;
 
@@ -112,3 +114,265 @@ fn assemble_pulley() {
         .save_as_lib(common::get_file_path("assemble_pulley.gds"))
         .unwrap();
 }
+
+#[test]
+fn max_deviation_pulley() {
+    common::init();
+    #[allow(non_snake_case)]
+    let WIDTH: [AbsoluteLength<f64>; 2] = [MICROMETER * 4., MICROMETER * 10.];
+    #[allow(non_snake_case)]
+    let COLOR: [LayerData; 2] = [LayerData::new(1, 0), LayerData::new(1, 1)];
+    #[allow(non_snake_case)]
+    let RESOLUTION: Resolution = Resolution::MaxDeviation(NANOMETER);
+    #[allow(non_snake_case)]
+    let RADIUS: AbsoluteLength<f64> = MICROMETER * 240.;
+    #[allow(non_snake_case)]
+    let PUL_ANG: Angle = Angle::from_deg(30.);
+
+    //every chord stays within a nanometer of the ring, with far fewer points than a fixed count would need
+    let points: Vec<_> = CircularArc::new_origin(
+        RADIUS,
+        (Angle::from_deg(0.), Angle::from_deg(360.)),
+        RESOLUTION,
+    )
+    .into_iter()
+    .collect();
+    assert!(points.len() < 8001);
+    for w in points.windows(2) {
+        let mid = [(w[0][0] + w[1][0]) / 2., (w[0][1] + w[1][1]) / 2.];
+        let sagitta =
+            (RADIUS - MICROMETER * (mid[0] / MICROMETER).hypot(mid[1] / MICROMETER)) / NANOMETER;
+        assert!(sagitta <= 1. + 1e-6);
+    }
+
+    let mut cursor: Assembler<_, _> = Assembler::new("topcell", COLOR, WIDTH, RESOLUTION);
+    cursor
+        .set_pos([zero(), RADIUS])
+        .set_dir(Angle::from_deg(0.))
+        .turn(RADIUS, -PUL_ANG / 2.)
+        .turn(RADIUS, PUL_ANG / 2.)
+        .extend(MICROMETER * 100.);
+    cursor
+        .into_cell()
+        .save_as_lib(common::get_file_path("max_deviation_pulley.gds"))
+        .unwrap();
+}
+
+#[test]
+fn component_ports() {
+    common::init();
+    #[allow(non_snake_case)]
+    let WIDTH: [AbsoluteLength<f64>; 2] = [MICROMETER * 4., MICROMETER * 10.];
+    #[allow(non_snake_case)]
+    let COLOR: [LayerData; 2] = [LayerData::new(1, 0), LayerData::new(1, 1)];
+
+    let mut cursor: Assembler<_, _> = Assembler::new(
+        "straight",
+        COLOR,
+        WIDTH,
+        Resolution::MinDistance(NANOMETER * 20.),
+    );
+    cursor
+        .add_port("in")
+        .extend(MICROMETER * 100.)
+        .add_port("out");
+    let mut cell = cursor.into_cell();
+    let input = cell.port("in").unwrap().clone().rev();
+    cell.add_port(input);
+    assert_eq!(cell.ports().len(), 2);
+    assert!(cell
+        .port("in")
+        .unwrap()
+        .dir()
+        .approx_eq(Angle::from_deg(180.), (1e-12, 1)));
+    assert_eq!(cell.port("out").unwrap().width(), WIDTH[0]);
+
+    let mut r = cell.into_ref_at([MICROMETER * 10., MICROMETER * 20.]);
+    r.set_rot(Angle::from_deg(90.));
+    let input = r.port("in").unwrap();
+    assert!(input
+        .pos()
+        .approx_eq([MICROMETER * 10., MICROMETER * 20.].into(), (1e-12, 1)));
+    assert!(input.dir().approx_eq(Angle::from_deg(270.), (1e-12, 1)));
+    let output = r.port("out").unwrap();
+    assert!(output
+        .pos()
+        .approx_eq([MICROMETER * 10., MICROMETER * 120.].into(), (1e-12, 1)));
+    assert!(output.dir().approx_eq(Angle::from_deg(90.), (1e-12, 1)));
+    assert_eq!(output.layers(), &Group::from(COLOR));
+
+    let array = r.into_array_ref(
+        [zero(), zero()],
+        2,
+        [zero(), MICROMETER * 100.],
+        2,
+        [MICROMETER * 400., zero()],
+    );
+    //the cell is shared until the reference is gone
+    drop(r);
+    let output = array.port("out", 1, 1).unwrap();
+    assert!(output
+        .pos()
+        .approx_eq([MICROMETER * 200., MICROMETER * 150.].into(), (1e-12, 1)));
+    assert_eq!(array.ports(0, 1).len(), 2);
+    let mut top_cell = DgirCell::new("component_ports");
+    top_cell.push(array);
+    top_cell
+        .save_as_lib(common::get_file_path("component_ports.gds"))
+        .unwrap();
+}
+
+#[test]
+fn connect_ports() {
+    common::init();
+    #[allow(non_snake_case)]
+    let WIDTH: [AbsoluteLength<f64>; 2] = [MICROMETER * 4., MICROMETER * 10.];
+    #[allow(non_snake_case)]
+    let COLOR: [LayerData; 2] = [LayerData::new(1, 0), LayerData::new(1, 1)];
+    #[allow(non_snake_case)]
+    let RESOLUTION: Resolution = Resolution::MinDistance(NANOMETER * 20.);
+
+    let bend = || {
+        let mut cursor: Assembler<_, _> = Assembler::new("bend", COLOR, WIDTH, RESOLUTION);
+        cursor
+            .add_port("in")
+            .turn(MICROMETER * 20., Angle::from_deg(90.))
+            .add_port("out");
+        let mut cell = cursor.into_cell();
+        let input = cell.port("in").unwrap().clone().rev();
+        cell.add_port(input);
+        cell
+    };
+
+    let first = bend().into_ref();
+    let mut second = first.clone();
+    second
+        .connect("in", &first.port("out").unwrap().into())
+        .unwrap();
+    let (a, b) = (first.port("out").unwrap(), second.port("in").unwrap());
+    assert!(b.pos().approx_eq(a.pos(), (1e-12, 1)));
+    assert!((b.dir() - a.dir()).approx_eq(Angle::from_deg(180.), (1e-12, 1)));
+    assert!(second
+        .port("out")
+        .unwrap()
+        .pos()
+        .approx_eq([zero(), MICROMETER * 40.].into(), (1e-12, 1)));
+
+    let mut cursor: Assembler<_, _> = Assembler::new("connect_ports", COLOR, WIDTH, RESOLUTION);
+    cursor
+        .extend(MICROMETER * 50.)
+        .place(bend(), "in", "out")
+        .unwrap()
+        .extend(MICROMETER * 50.);
+    assert!(cursor
+        .cell_cur
+        .cursor
+        .pos
+        .approx_eq([MICROMETER * 70., MICROMETER * 70.].into(), (1e-12, 1)));
+    assert!(cursor
+        .cell_cur
+        .cursor
+        .dir
+        .approx_eq(Angle::from_deg(90.), (1e-12, 1)));
+
+    //a missing port places nothing and leaves the cursor where it is
+    let (end, extent) = (cursor.cell_cur.cursor, cursor.bounding_box());
+    assert!(cursor.place(bend(), "in", "through").is_none());
+    assert!(cursor.place(bend(), "through", "out").is_none());
+    assert!(cursor.cell_cur.cursor.pos.approx_eq(end.pos, (1e-12, 1)));
+    assert!(cursor.cell_cur.cursor.dir.approx_eq(end.dir, (1e-12, 1)));
+    assert_eq!(cursor.bounding_box(), extent);
+    assert!(second.connect("through", &end).is_none());
+    cursor
+        .into_cell()
+        .save_as_lib(common::get_file_path("connect_ports.gds"))
+        .unwrap();
+}
+
+#[test]
+fn mirrored_component() {
+    common::init();
+    #[allow(non_snake_case)]
+    let WIDTH: [AbsoluteLength<f64>; 2] = [MICROMETER * 4., MICROMETER * 10.];
+    #[allow(non_snake_case)]
+    let COLOR: [LayerData; 2] = [LayerData::new(1, 0), LayerData::new(1, 1)];
+    #[allow(non_snake_case)]
+    let RESOLUTION: Resolution = Resolution::MinDistance(NANOMETER * 20.);
+
+    let bend = || {
+        let mut cursor: Assembler<_, _> = Assembler::new("left_bend", COLOR, WIDTH, RESOLUTION);
+        cursor
+            .add_port("in")
+            .turn(MICROMETER * 20., Angle::from_deg(90.))
+            .add_port("out");
+        let mut cell = cursor.into_cell();
+        let input = cell.port("in").unwrap().clone().rev();
+        cell.add_port(input);
+        cell
+    };
+
+    //the reflected reference is the right-handed bend
+    let mut r = bend().into_ref();
+    r.set_mirror(true);
+    assert!(r.mirrored());
+    let output = r.port("out").unwrap();
+    assert!(output
+        .pos()
+        .approx_eq([MICROMETER * 20., MICROMETER * -20.].into(), (1e-12, 1)));
+    assert!(output.dir().approx_eq(Angle::from_deg(-90.), (1e-12, 1)));
+
+    //a mirrored cursor draws and places the other hand of the same route
+    let mut cursor: Assembler<_, _> =
+        Assembler::new("mirrored_component", COLOR, WIDTH, RESOLUTION);
+    cursor
+        .set_mirrored(true)
+        .extend(MICROMETER * 50.)
+        .place(bend(), "in", "out")
+        .unwrap()
+        .turn(MICROMETER * 20., Angle::from_deg(-90.))
+        .extend(MICROMETER * 50.);
+    assert!(cursor.cell_cur.mirrored());
+    let end = cursor.cell_cur.cursor;
+    assert!(end
+        .pos
+        .approx_eq([MICROMETER * 140., MICROMETER * -40.].into(), (1e-9, 1)));
+    assert!(end.dir.approx_eq(Angle::from_deg(0.), (1e-12, 1)));
+    cursor
+        .into_cell()
+        .save_as_lib(common::get_file_path("mirrored_component.gds"))
+        .unwrap();
+}
+
+#[test]
+fn route_ports() {
+    common::init();
+    #[allow(non_snake_case)]
+    let WIDTH: [AbsoluteLength<f64>; 2] = [MICROMETER * 4., MICROMETER * 10.];
+    #[allow(non_snake_case)]
+    let COLOR: [LayerData; 2] = [LayerData::new(1, 0), LayerData::new(1, 1)];
+
+    let mut cursor: Assembler<_, _> = Assembler::new(
+        "route_ports",
+        COLOR,
+        WIDTH,
+        Resolution::MinDistance(NANOMETER * 20.),
+    );
+    cursor
+        .set_radius(MICROMETER * 50.)
+        .set_merge(true)
+        .add_port("in")
+        .route_to(
+            [MICROMETER * 300., MICROMETER * 120.],
+            Angle::from_deg(180.),
+        )
+        .unwrap()
+        .add_port("out");
+    let cell = cursor.into_cell();
+    let output = cell.port("out").unwrap();
+    assert!(output
+        .pos()
+        .approx_eq([MICROMETER * 300., MICROMETER * 120.].into(), (1e-12, 1)));
+    assert!(output.dir().approx_eq(Angle::from_deg(180.), (1e-12, 1)));
+    cell.save_as_lib(common::get_file_path("route_ports.gds"))
+        .unwrap();
+}
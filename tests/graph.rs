@@ -3,16 +3,23 @@ use dgir::{
     color::LayerData,
     cursor::{ArcCurve, Assembler, CellCursor, Cursor, Rect},
     draw::{
+        bbox::Bounded,
+        boolean::BooleanOp,
+        coordinate::Coordinate,
         curve::{
             groups::{Compound, Group},
-            Sweep,
+            Curve, Sweep,
         },
+        offset::JoinStyle,
+        parametric::ParametricCurve,
+        transfer::CommonTrans,
         CircularArc, Line, Resolution,
     },
-    gds::{DgirCell, DgirLibrary},
+    gds::{DgirCell, DgirLibrary, Element, PathType},
     units::{AbsoluteLength, Angle},
     zero, MICROMETER, MILLIMETER, NANOMETER,
 };
+use float_cmp::ApproxEq;
 
 mod common;
 
@@ -107,6 +114,56 @@ fn cursor() {
         .unwrap();
 }
 
+#[test]
+fn mirrored_cursor() {
+    common::init();
+    #[allow(non_snake_case)]
+    let WIDTH: [AbsoluteLength<f64>; 2] = [MICROMETER * 4., MICROMETER * 10.];
+    #[allow(non_snake_case)]
+    let COLOR: [LayerData; 2] = [LayerData::new(1, 0), LayerData::new(1, 1)];
+    #[allow(non_snake_case)]
+    let RESOLUTION: Resolution = Resolution::MinDistance(20. * NANOMETER);
+
+    let cir: ArcCurve<[_; 2]> = ArcCurve::new(
+        CircularArc::new(
+            MICROMETER * 20.,
+            [zero(), zero()],
+            (Angle::from_deg(-90.), Angle::from_deg(0.)),
+            RESOLUTION,
+        ),
+        WIDTH,
+    );
+    let (mut left, mut right): (Cursor, Cursor) = (Cursor::default(), Cursor::default());
+    let drawn = left.assemble(cir.into_group());
+    let mirrored = right.assemble_mirrored(cir.into_group());
+    //the mirrored cursor turns the other way and draws the reflection about its direction of travel
+    assert!(left
+        .pos
+        .approx_eq([MICROMETER * 20., MICROMETER * 20.].into(), (1e-9, 1)));
+    assert!(right
+        .pos
+        .approx_eq([MICROMETER * 20., MICROMETER * -20.].into(), (1e-9, 1)));
+    assert!(right.dir.approx_eq(-left.dir, (1e-12, 1)));
+    let reflected: Vec<_> = drawn.mirror_x().0.into_iter().flatten().collect();
+    let mirrored_points: Vec<_> = mirrored.0.into_iter().flatten().collect();
+    assert_eq!(reflected.len(), mirrored_points.len());
+    assert!(reflected
+        .into_iter()
+        .zip(mirrored_points)
+        .all(|(a, b)| a.approx_eq(b, (1e-9, 1))));
+
+    let mut topcell: DgirCell = DgirCell::new("mirrored_cursor");
+    topcell.push(left.assemble(cir.into_group()).color(Group::from(COLOR)));
+    topcell.push(
+        right
+            .assemble_mirrored(cir.into_group())
+            .color(Group::from(COLOR)),
+    );
+    topcell
+        .save_as_lib(common::get_file_path("mirrored_cursor.gds"))
+        .unwrap();
+}
+
 #[test]
 fn cell_cursor() {
     common::init();
@@ -168,6 +225,92 @@ fn assembler() {
         .unwrap();
 }
 
+#[test]
+fn euler_turn() {
+    common::init();
+    #[allow(non_snake_case)]
+    let WIDTH: [AbsoluteLength<f64>; 2] = [MICROMETER * 4., MICROMETER * 10.];
+    #[allow(non_snake_case)]
+    let COLOR: [LayerData; 2] = [LayerData::new(1, 0), LayerData::new(1, 1)];
+    #[allow(non_snake_case)]
+    let RESOLUTION: Resolution = Resolution::MinDistance(20. * NANOMETER);
+
+    let mut cur: Assembler<_, _> = Assembler::new("euler_turn", COLOR, WIDTH, RESOLUTION);
+    cur.euler_turn(MICROMETER * 100., Angle::from_deg(-90.), 0.5);
+    //the bend is symmetric, so it ends on the diagonal, further out than a circular one of the minimum radius
+    let end = cur.cell_cur.cursor;
+    assert!(end.dir.approx_eq(Angle::from_deg(-90.), (1e-9, 1)));
+    assert!((end.pos[0] + end.pos[1]).abs() < NANOMETER);
+    assert!(end.pos[0] > MICROMETER * 100.);
+    cur.extend(MICROMETER * 20.);
+    cur.into_cell()
+        .save_as_lib(common::get_file_path("euler_turn.gds"))
+        .unwrap();
+}
+
+#[test]
+fn sbend() {
+    common::init();
+    #[allow(non_snake_case)]
+    let WIDTH: [AbsoluteLength<f64>; 2] = [MICROMETER * 4., MICROMETER * 10.];
+    #[allow(non_snake_case)]
+    let COLOR: [LayerData; 2] = [LayerData::new(1, 0), LayerData::new(1, 1)];
+    #[allow(non_snake_case)]
+    let RESOLUTION: Resolution = Resolution::MinDistance(20. * NANOMETER);
+
+    let mut cur: Assembler<_, _> = Assembler::new("sbend", COLOR, WIDTH, RESOLUTION);
+    cur.set_dir(Angle::from_deg(90.));
+    cur.sbend(MICROMETER * 100., MICROMETER * 20.);
+    //the offset is to the left of the direction, which is kept
+    let end = cur.cell_cur.cursor;
+    assert!(end
+        .pos
+        .approx_eq([MICROMETER * -20., MICROMETER * 100.].into(), (1e-9, 1)));
+    assert!(end.dir.approx_eq(Angle::from_deg(90.), (1e-12, 1)));
+    cur.extend(MICROMETER * 20.);
+    cur.into_cell()
+        .save_as_lib(common::get_file_path("sbend.gds"))
+        .unwrap();
+}
+
+#[test]
+fn parametric_curve() {
+    common::init();
+    #[allow(non_snake_case)]
+    let WIDTH: [AbsoluteLength<f64>; 2] = [MICROMETER * 4., MICROMETER * 10.];
+    #[allow(non_snake_case)]
+    let COLOR: [LayerData; 2] = [LayerData::new(1, 0), LayerData::new(1, 1)];
+    #[allow(non_snake_case)]
+    let RESOLUTION: Resolution = Resolution::MinDistance(MICROMETER);
+
+    let curve = ParametricCurve::new(
+        |t: f64| Coordinate::from([MICROMETER * t, MICROMETER * 10. * (t / 20.).sin()]),
+        (0., 40. * std::f64::consts::PI),
+        RESOLUTION,
+    );
+    //points are resampled at equal arc length, no closer than the minimum distance
+    let points: Vec<_> = curve.into_iter().collect();
+    let chords: Vec<_> = points
+        .windows(2)
+        .map(|w| (w[1][0] - w[0][0]) / MICROMETER)
+        .zip(points.windows(2).map(|w| (w[1][1] - w[0][1]) / MICROMETER))
+        .map(|(x, y)| x.hypot(y))
+        .collect();
+    let (shortest, longest) = chords
+        .iter()
+        .fold((f64::MAX, 0f64), |(lo, hi), c| (lo.min(*c), hi.max(*c)));
+    assert!(shortest > 1. - 1e-3);
+    assert!(longest < 2.);
+    assert!(longest - shortest < 1e-3);
+
+    let mut cur: Assembler<_, _> = Assembler::new("parametric_curve", COLOR, WIDTH, RESOLUTION);
+    cur.sweep_in(curve);
+    cur.extend(MICROMETER * 20.);
+    cur.into_cell()
+        .save_as_lib(common::get_file_path("parametric_curve.gds"))
+        .unwrap();
+}
+
 #[test]
 fn array_ref() {
     common::init();
@@ -198,3 +341,308 @@ fn array_ref() {
         .save_as_lib(common::get_file_path("array_ref.gds"))
         .unwrap();
 }
+
+#[test]
+fn load_library() {
+    common::init();
+    let mut cell = DgirCell::new("unit");
+    cell.push(
+        Rect::new(
+            Line::new([-MICROMETER * 10., zero()], [MICROMETER * 10., zero()]),
+            [MICROMETER * 5.],
+        )
+        .into_group()
+        .color(LayerData::new(1, 0)),
+    );
+    let mut mid_cell = DgirCell::new("mid_cell");
+    mid_cell.push(cell.into_ref_at([MICROMETER * 20., MICROMETER * 30.]));
+    let mut top_cell = DgirCell::new("top_cell");
+    top_cell.push(mid_cell.into_array_ref(
+        [zero(), zero()],
+        2,
+        [zero(), MICROMETER * 200.],
+        3,
+        [MICROMETER * 300., zero()],
+    ));
+    let path = common::get_file_path("load_library.gds");
+    top_cell.save_as_lib(&path).unwrap();
+
+    let lib = DgirLibrary::<f64, AbsoluteLength<f64>>::load(&path).unwrap();
+    assert_eq!(lib.units().database(), NANOMETER);
+    assert_eq!(lib.units().user(), MICROMETER);
+    let cells = lib.into_cells();
+    assert_eq!(cells.len(), 1);
+    assert_eq!(cells[0].name, "top_cell");
+    match &cells[0].as_ref()[..] {
+        [Element::ARef(ar)] => assert_eq!(ar.id(), "mid_cell"),
+        e => panic!("unexpected elements {:?}", e),
+    }
+    let mut reloaded: DgirLibrary<f64, AbsoluteLength<f64>> = DgirLibrary::new("reloaded");
+    for c in cells {
+        reloaded.push(c);
+    }
+    reloaded
+        .save(common::get_file_path("load_library_resaved.gds"))
+        .unwrap();
+}
+
+#[test]
+fn bounding_boxes() {
+    common::init();
+    let mut cell = DgirCell::new("unit");
+    cell.push(
+        Rect::new(
+            Line::new([zero(), zero()], [MICROMETER * 20., zero()]),
+            [MICROMETER * 4.],
+        )
+        .into_group()
+        .color(LayerData::new(1, 0)),
+    );
+    let mut r = cell.into_ref_at([MICROMETER * 100., zero()]);
+    r.set_rot(Angle::from_deg(90.));
+    let mut top_cell = DgirCell::new("top_cell");
+    top_cell.push(r);
+    let mut top_ref = top_cell.into_ref();
+    let bbox = top_ref.bounding_box().unwrap();
+    assert!(bbox
+        .min()
+        .approx_eq(Coordinate::from([MICROMETER * 98., zero()]), (1e-12, 1)));
+    assert!(bbox.max().approx_eq(
+        Coordinate::from([MICROMETER * 102., MICROMETER * 20.]),
+        (1e-12, 1)
+    ));
+    let mut array = top_ref.into_array_ref(
+        [MICROMETER * 10., zero()],
+        4,
+        [MICROMETER * 10., MICROMETER * 400.],
+        2,
+        [MICROMETER * 110., zero()],
+    );
+    let bbox = array.bounding_box().unwrap();
+    assert!(bbox
+        .min()
+        .approx_eq(Coordinate::from([MICROMETER * 108., zero()]), (1e-12, 1)));
+    assert!(bbox.max().approx_eq(
+        Coordinate::from([MICROMETER * 187., MICROMETER * 220.]),
+        (1e-12, 1)
+    ));
+    assert!(bbox.width().approx_eq(MICROMETER * 79., (1e-12, 1)));
+
+    let mut cell = DgirCell::new("unit");
+    cell.push(
+        Rect::new(
+            Line::new([-MICROMETER * 10., zero()], [MICROMETER * 10., zero()]),
+            [MICROMETER * 5.],
+        )
+        .into_group()
+        .color(LayerData::new(1, 0)),
+    );
+    let mut mid_cell = DgirCell::new("mid_cell");
+    mid_cell.push(cell.into_ref_at([MICROMETER * 20., MICROMETER * 30.]));
+    let mut top_cell = DgirCell::new("top_cell");
+    top_cell.push(mid_cell.into_array_ref(
+        [zero(), zero()],
+        2,
+        [zero(), MICROMETER * 200.],
+        3,
+        [MICROMETER * 300., zero()],
+    ));
+    let bbox = top_cell.bounding_box().unwrap();
+    //two instances along `col_end` and three along `row_end`
+    assert!(bbox.min().approx_eq(
+        Coordinate::from([MICROMETER * 10., MICROMETER * 27.5]),
+        (1e-12, 1)
+    ));
+    assert!(bbox.max().approx_eq(
+        Coordinate::from([MICROMETER * 180., MICROMETER * (32.5 + 400. / 3.)]),
+        (1e-12, 1)
+    ));
+    let path = common::get_file_path("bounding_boxes.gds");
+    top_cell.save_as_lib(&path).unwrap();
+    let mut cells = DgirLibrary::<f64, AbsoluteLength<f64>>::load(&path)
+        .unwrap()
+        .into_cells();
+    let loaded = cells[0].bounding_box().unwrap();
+    assert!(loaded.min().approx_eq(bbox.min(), (1e-12, 1)));
+    assert!(loaded.max().approx_eq(bbox.max(), (1e-12, 1)));
+}
+
+#[test]
+fn boolean_layers() {
+    common::init();
+    #[allow(non_snake_case)]
+    let WIDTH: [AbsoluteLength<f64>; 2] = [MICROMETER * 4., MICROMETER * 10.];
+
+    #[allow(non_snake_case)]
+    let COLOR: [LayerData; 2] = [LayerData::new(1, 0), LayerData::new(1, 1)];
+
+    #[allow(non_snake_case)]
+    let RESOLUTION: Resolution = Resolution::MinDistance(200. * NANOMETER);
+
+    let mut cur: Assembler<_, _> = Assembler::new("topcell", Group::from(COLOR), WIDTH, RESOLUTION);
+    cur.extend(MICROMETER * 20.)
+        .turn(MICROMETER * 50., Angle::from_deg(90.))
+        .extend(MICROMETER * 20.)
+        .turn(MICROMETER * 50., Angle::from_deg(-90.));
+    let mut cell = cur.into_cell();
+    cell.merge_layer(COLOR[0]).boolean(
+        COLOR[1],
+        COLOR[0],
+        BooleanOp::Difference,
+        LayerData::new(2, 0),
+    );
+    assert_eq!(cell.as_ref().len(), 7);
+    cell.save_as_lib(common::get_file_path("boolean_layers.gds"))
+        .unwrap();
+}
+
+#[test]
+fn offset_layers() {
+    common::init();
+    #[allow(non_snake_case)]
+    let WIDTH: [AbsoluteLength<f64>; 2] = [MICROMETER * 4., MICROMETER * 10.];
+
+    #[allow(non_snake_case)]
+    let COLOR: [LayerData; 2] = [LayerData::new(1, 0), LayerData::new(1, 1)];
+
+    #[allow(non_snake_case)]
+    let RESOLUTION: Resolution = Resolution::MinDistance(200. * NANOMETER);
+
+    let mut cur: Assembler<_, _> = Assembler::new("topcell", Group::from(COLOR), WIDTH, RESOLUTION);
+    cur.extend(MICROMETER * 20.)
+        .turn(MICROMETER * 50., Angle::from_deg(90.))
+        .extend(MICROMETER * 20.)
+        .turn(MICROMETER * 50., Angle::from_deg(-90.));
+    let mut cell = cur.into_cell();
+    let n = cell.as_ref().len();
+    cell.offset(
+        COLOR[0],
+        MICROMETER * 2.,
+        JoinStyle::Round(RESOLUTION),
+        LayerData::new(3, 0),
+    );
+    assert_eq!(cell.as_ref().len(), n + 1);
+    //the waveguide spans (0, -2) to (120, 122), grown by 2 on every side; the round joins only approach the corners
+    let elements: &mut Vec<Element<_>> = cell.as_mut();
+    let bbox = elements.last_mut().unwrap().bounding_box().unwrap();
+    let expected = [[-2., -4.], [122., 124.]];
+    for (corner, exp) in [bbox.min(), bbox.max()].into_iter().zip(expected) {
+        for k in 0..2 {
+            assert!(((corner[k] - MICROMETER * exp[k]) / NANOMETER).abs() < 10.);
+        }
+    }
+    cell.save_as_lib(common::get_file_path("offset_layers.gds"))
+        .unwrap();
+}
+
+#[test]
+fn path_types() {
+    common::init();
+    let l_shape = || {
+        Curve::new(vec![
+            Coordinate::from([zero(), zero()]),
+            Coordinate::from([MICROMETER * 10., zero()]),
+            Coordinate::from([MICROMETER * 10., MICROMETER * 10.]),
+        ])
+    };
+    let mut cell = DgirCell::new("path_types");
+    for (i, t) in [
+        PathType::Flush,
+        PathType::Round,
+        PathType::HalfWidth,
+        PathType::Custom(MICROMETER, MICROMETER * 3.),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        cell.push((l_shape(), MICROMETER * 2., t).color(LayerData::new(i as i16, 0)));
+    }
+    let path = common::get_file_path("path_types.gds");
+    cell.save_as_lib(&path).unwrap();
+
+    let mut cells = DgirLibrary::<f64, AbsoluteLength<f64>>::load(&path)
+        .unwrap()
+        .into_cells();
+    let types: Vec<_> = cells[0]
+        .as_ref()
+        .iter()
+        .map(|e| match e {
+            Element::Path(p) => p.path_type,
+            e => panic!("unexpected element {:?}", e),
+        })
+        .collect();
+    assert_eq!(types[1], PathType::Round);
+    assert_eq!(types[2], PathType::HalfWidth);
+    match types[3] {
+        PathType::Custom(begin, end) => {
+            assert!(begin.approx_eq(MICROMETER, (1e-12, 1)));
+            assert!(end.approx_eq(MICROMETER * 3., (1e-12, 1)));
+        }
+        t => panic!("unexpected path type {:?}", t),
+    }
+
+    let mut cell = cells.remove(0);
+    cell.paths_to_polygons(Resolution::MinDistance(NANOMETER * 20.));
+    assert!(cell
+        .as_ref()
+        .iter()
+        .all(|e| matches!(e, Element::Polygon(_))));
+    //the custom extensions reach one micrometer before the begin and three past the end
+    let bbox = cell.bounding_box().unwrap();
+    assert!(bbox.min().approx_eq(
+        Coordinate::from([-MICROMETER * 1., -MICROMETER * 1.]),
+        (1e-12, 1)
+    ));
+    assert!(bbox.max().approx_eq(
+        Coordinate::from([MICROMETER * 11., MICROMETER * 13.]),
+        (1e-12, 1)
+    ));
+    cell.rename("path_polygons".to_string());
+    cell.save_as_lib(common::get_file_path("path_polygons.gds"))
+        .unwrap();
+}
+
+#[test]
+fn path_bounding_box() {
+    common::init();
+    let path = |points: &[(f64, f64)], t| {
+        let curve = Curve::new(
+            points
+                .iter()
+                .map(|(x, y)| Coordinate::from([MICROMETER * *x, MICROMETER * *y]))
+                .collect::<Vec<_>>(),
+        );
+        (curve, MICROMETER * 2., t).color(LayerData::new(1, 0))
+    };
+    //the extensions are counted without converting to polygons
+    let mut cell = DgirCell::new("extended");
+    cell.push(path(
+        &[(0., 0.), (10., 0.), (10., 10.)],
+        PathType::Custom(MICROMETER, MICROMETER * 3.),
+    ));
+    let bbox = cell.bounding_box().unwrap();
+    assert!(bbox.min().approx_eq(
+        Coordinate::from([-MICROMETER * 1., -MICROMETER * 1.]),
+        (1e-12, 1)
+    ));
+    assert!(bbox.max().approx_eq(
+        Coordinate::from([MICROMETER * 11., MICROMETER * 13.]),
+        (1e-12, 1)
+    ));
+
+    //the miter of a sharp joint reaches far past the joint
+    let mut cell = DgirCell::new("mitered");
+    cell.push(path(&[(0., 0.), (10., 0.), (0., 2.)], PathType::Flush));
+    let bbox = cell.bounding_box().unwrap();
+    assert!(bbox.max()[0] > MICROMETER * 15.);
+    cell.paths_to_polygons(Resolution::MinDistance(NANOMETER * 20.));
+    let polygon = cell.bounding_box().unwrap();
+    assert!(bbox.min().approx_eq(polygon.min(), (1e-12, 1)));
+    assert!(bbox.max().approx_eq(polygon.max(), (1e-12, 1)));
+
+    //a single point has no outline and is dropped
+    let mut cell = DgirCell::new("point");
+    cell.push(path(&[(0., 0.)], PathType::Flush));
+    cell.paths_to_polygons(Resolution::MinDistance(NANOMETER * 20.));
+    assert!(cell.as_ref().is_empty());
+}